@@ -1,6 +1,7 @@
 use crate::config::Config;
 use crate::error::{PfpError, Result};
-use crate::models::{DeploymentSchedule, WorkPool};
+use crate::models::{DeploymentSchedule, OrchestrationResult, WorkPool};
+use chrono::{DateTime, Utc};
 use reqwest::Client;
 use serde::de::DeserializeOwned;
 use std::collections::HashMap;
@@ -40,6 +41,9 @@ impl PrefectClient {
         path: &str,
         body: &serde_json::Value,
     ) -> Result<T> {
+        if !is_query_path(path) {
+            self.ensure_writable("POST", path)?;
+        }
         let url = format!("{}{}", self.config.api_url, path);
         let mut req = self.client.post(&url);
         if let Some(auth) = &self.config.auth_header {
@@ -57,6 +61,7 @@ impl PrefectClient {
     }
 
     pub async fn patch_no_content(&self, path: &str, body: &serde_json::Value) -> Result<()> {
+        self.ensure_writable("PATCH", path)?;
         let url = format!("{}{}", self.config.api_url, path);
         let mut req = self.client.patch(&url);
         if let Some(auth) = &self.config.auth_header {
@@ -73,6 +78,16 @@ impl PrefectClient {
         Ok(())
     }
 
+    /// Refuse a state-changing request in read-only mode. Every POST other
+    /// than a `/filter` or `/count` query, and every PATCH or DELETE, must
+    /// pass through here before it is sent.
+    fn ensure_writable(&self, method: &str, path: &str) -> Result<()> {
+        if self.config.read_only {
+            return Err(PfpError::ReadOnly(format!("{} {}", method, path)));
+        }
+        Ok(())
+    }
+
     // -- Prefect API methods --
 
     pub async fn list_deployments(&self) -> Result<Vec<serde_json::Value>> {
@@ -161,21 +176,33 @@ impl PrefectClient {
         self.get(&format!("/flow_runs/{}", flow_run_id)).await
     }
 
+    /// Fetch up to `limit` flow runs matching `filter`, newest first, following
+    /// Prefect pagination past the first page.
     pub async fn filter_flow_runs(
         &self,
-        deployment_id: &str,
+        filter: &FlowRunFilter,
         limit: usize,
     ) -> Result<Vec<serde_json::Value>> {
-        let body = serde_json::json!({
-            "flow_runs": {
-                "deployment_id": {
-                    "any_": [deployment_id]
-                }
-            },
-            "sort": "START_TIME_DESC",
-            "limit": limit
-        });
-        self.post("/flow_runs/filter", &body).await
+        const PAGE_SIZE: usize = 200;
+        let mut runs = Vec::new();
+
+        while runs.len() < limit {
+            let page_limit = (limit - runs.len()).min(PAGE_SIZE);
+            let mut body = filter.to_body();
+            body["sort"] = serde_json::json!("START_TIME_DESC");
+            body["limit"] = serde_json::json!(page_limit);
+            body["offset"] = serde_json::json!(runs.len());
+
+            let page: Vec<serde_json::Value> = self.post("/flow_runs/filter", &body).await?;
+            let page_len = page.len();
+            runs.extend(page);
+
+            if page_len < page_limit {
+                break;
+            }
+        }
+
+        Ok(runs)
     }
 
     pub async fn filter_flow_runs_global(&self, limit: usize) -> Result<Vec<serde_json::Value>> {
@@ -188,7 +215,42 @@ impl PrefectClient {
 
     pub async fn get_flow_run_logs(
         &self,
-        flow_run_id: &str,
+        filter: &LogFilter,
+        limit: usize,
+        start_offset: usize,
+    ) -> Result<Vec<serde_json::Value>> {
+        self.fetch_logs(filter, "TIMESTAMP_ASC", limit, start_offset)
+            .await
+    }
+
+    /// Fetch the newest `count` matching log entries, returned oldest first,
+    /// without paging through the history before them.
+    pub async fn get_flow_run_logs_tail(
+        &self,
+        filter: &LogFilter,
+        count: usize,
+    ) -> Result<Vec<serde_json::Value>> {
+        let mut logs = self.fetch_logs(filter, "TIMESTAMP_DESC", count, 0).await?;
+        logs.reverse();
+        Ok(logs)
+    }
+
+    /// Fetch up to `limit` matching log entries newest first, skipping the
+    /// `start_offset` newest. Used to page back through the history.
+    pub async fn get_flow_run_logs_newest_first(
+        &self,
+        filter: &LogFilter,
+        limit: usize,
+        start_offset: usize,
+    ) -> Result<Vec<serde_json::Value>> {
+        self.fetch_logs(filter, "TIMESTAMP_DESC", limit, start_offset)
+            .await
+    }
+
+    async fn fetch_logs(
+        &self,
+        filter: &LogFilter,
+        sort: &str,
         limit: usize,
         start_offset: usize,
     ) -> Result<Vec<serde_json::Value>> {
@@ -196,17 +258,13 @@ impl PrefectClient {
         let mut all_logs = Vec::new();
         let mut offset: usize = start_offset;
 
-        loop {
+        while all_logs.len() < limit {
             let remaining = limit - all_logs.len();
             let page_limit = remaining.min(PAGE_SIZE);
 
             let body = serde_json::json!({
-                "logs": {
-                    "flow_run_id": {
-                        "any_": [flow_run_id]
-                    }
-                },
-                "sort": "TIMESTAMP_ASC",
+                "logs": filter.to_body(),
+                "sort": sort,
                 "limit": page_limit,
                 "offset": offset
             });
@@ -215,7 +273,7 @@ impl PrefectClient {
             let page_len = page.len();
             all_logs.extend(page);
 
-            if page_len < page_limit || all_logs.len() >= limit {
+            if page_len < page_limit {
                 break;
             }
 
@@ -225,6 +283,34 @@ impl PrefectClient {
         Ok(all_logs)
     }
 
+    /// Fetch every task run of one flow run in expected start order.
+    pub async fn filter_task_runs(&self, flow_run_id: &str) -> Result<Vec<serde_json::Value>> {
+        const PAGE_SIZE: usize = 200;
+        let mut task_runs = Vec::new();
+
+        loop {
+            let body = serde_json::json!({
+                "task_runs": {
+                    "flow_run_id": {
+                        "any_": [flow_run_id]
+                    }
+                },
+                "sort": "EXPECTED_START_TIME_ASC",
+                "limit": PAGE_SIZE,
+                "offset": task_runs.len()
+            });
+            let page: Vec<serde_json::Value> = self.post("/task_runs/filter", &body).await?;
+            let page_len = page.len();
+            task_runs.extend(page);
+
+            if page_len < PAGE_SIZE {
+                break;
+            }
+        }
+
+        Ok(task_runs)
+    }
+
     pub async fn set_deployment_paused(&self, deployment_id: &str, paused: bool) -> Result<()> {
         let body = serde_json::json!({ "paused": paused });
         self.patch_no_content(&format!("/deployments/{}", deployment_id), &body)
@@ -253,16 +339,53 @@ impl PrefectClient {
         .await
     }
 
-    pub async fn cancel_flow_run(&self, flow_run_id: &str) -> Result<serde_json::Value> {
+    /// Ask Prefect to cancel a flow run. By default this proposes
+    /// `CANCELLING`, so Prefect's orchestration can tear down the run's
+    /// infrastructure before marking it `CANCELLED`. `force` sets `CANCELLED`
+    /// directly and skips that. Fails unless Prefect accepts the proposal.
+    pub async fn cancel_flow_run(
+        &self,
+        flow_run_id: &str,
+        force: bool,
+    ) -> Result<OrchestrationResult> {
+        let state_type = if force { "CANCELLED" } else { "CANCELLING" };
         let body = serde_json::json!({
             "state": {
-                "type": "CANCELLED",
+                "type": state_type,
                 "message": "Cancelled via pfp CLI"
             },
-            "force": true
+            "force": force
         });
-        self.post(&format!("/flow_runs/{}/set_state", flow_run_id), &body)
-            .await
+        let result: OrchestrationResult = self
+            .post(&format!("/flow_runs/{}/set_state", flow_run_id), &body)
+            .await?;
+        if result.status != "ACCEPT" {
+            return Err(PfpError::NotAccepted(format!(
+                "the cancel ({}): {}",
+                result.status,
+                result.reason().unwrap_or("no reason given")
+            )));
+        }
+        Ok(result)
+    }
+
+    /// Every work pool, following pagination.
+    pub async fn list_work_pools(&self) -> Result<Vec<WorkPool>> {
+        const PAGE_SIZE: usize = 100;
+        let mut pools = Vec::new();
+        loop {
+            let body = serde_json::json!({
+                "limit": PAGE_SIZE,
+                "offset": pools.len()
+            });
+            let page: Vec<WorkPool> = self.post("/work_pools/filter", &body).await?;
+            let page_len = page.len();
+            pools.extend(page);
+            if page_len < PAGE_SIZE {
+                break;
+            }
+        }
+        Ok(pools)
     }
 
     pub async fn get_work_pool(&self, name: &str) -> Result<WorkPool> {
@@ -304,6 +427,125 @@ impl PrefectClient {
     }
 }
 
+/// Criteria for `/flow_runs/filter`. Empty fields place no restriction.
+#[derive(Debug, Default)]
+pub struct FlowRunFilter {
+    /// Exact flow run IDs
+    pub ids: Vec<String>,
+    pub deployment_id: Option<String>,
+    /// Prefect state types, e.g. `FAILED`
+    pub state_types: Vec<String>,
+    /// Lower bound on the expected start time
+    pub since: Option<DateTime<Utc>>,
+    /// Upper bound on the expected start time
+    pub until: Option<DateTime<Utc>>,
+    /// Runs must carry every listed tag
+    pub tags: Vec<String>,
+    pub work_pool: Option<String>,
+}
+
+impl FlowRunFilter {
+    fn to_body(&self) -> serde_json::Value {
+        let mut flow_runs = serde_json::Map::new();
+        if !self.ids.is_empty() {
+            flow_runs.insert("id".into(), serde_json::json!({ "any_": self.ids }));
+        }
+        if let Some(deployment_id) = &self.deployment_id {
+            flow_runs.insert(
+                "deployment_id".into(),
+                serde_json::json!({ "any_": [deployment_id] }),
+            );
+        }
+        if !self.state_types.is_empty() {
+            flow_runs.insert(
+                "state".into(),
+                serde_json::json!({ "type": { "any_": self.state_types } }),
+            );
+        }
+        // Expected start time is set for every run, including ones that crash
+        // before they ever start, so time windows never silently drop those.
+        let mut window = serde_json::Map::new();
+        if let Some(since) = self.since {
+            window.insert("after_".into(), serde_json::json!(since.to_rfc3339()));
+        }
+        if let Some(until) = self.until {
+            window.insert("before_".into(), serde_json::json!(until.to_rfc3339()));
+        }
+        if !window.is_empty() {
+            flow_runs.insert("expected_start_time".into(), window.into());
+        }
+        if !self.tags.is_empty() {
+            flow_runs.insert("tags".into(), serde_json::json!({ "all_": self.tags }));
+        }
+
+        let mut body = serde_json::Map::new();
+        if !flow_runs.is_empty() {
+            body.insert("flow_runs".into(), flow_runs.into());
+        }
+        if let Some(pool) = &self.work_pool {
+            body.insert(
+                "work_pools".into(),
+                serde_json::json!({ "name": { "any_": [pool] } }),
+            );
+        }
+        body.into()
+    }
+}
+
+/// Criteria for the `logs` section of `/logs/filter`.
+#[derive(Debug, Default)]
+pub struct LogFilter {
+    pub flow_run_ids: Vec<String>,
+    pub task_run_id: Option<String>,
+    /// Minimum numeric log level, e.g. 40 for ERROR
+    pub min_level: Option<u32>,
+    pub since: Option<DateTime<Utc>>,
+    pub until: Option<DateTime<Utc>>,
+}
+
+impl LogFilter {
+    pub fn for_flow_run(flow_run_id: &str) -> Self {
+        Self {
+            flow_run_ids: vec![flow_run_id.to_string()],
+            ..Default::default()
+        }
+    }
+
+    fn to_body(&self) -> serde_json::Value {
+        let mut logs = serde_json::Map::new();
+        logs.insert(
+            "flow_run_id".into(),
+            serde_json::json!({ "any_": self.flow_run_ids }),
+        );
+        if let Some(task_run_id) = &self.task_run_id {
+            logs.insert(
+                "task_run_id".into(),
+                serde_json::json!({ "any_": [task_run_id] }),
+            );
+        }
+        if let Some(min_level) = self.min_level {
+            logs.insert("level".into(), serde_json::json!({ "ge_": min_level }));
+        }
+        let mut window = serde_json::Map::new();
+        if let Some(since) = self.since {
+            window.insert("after_".into(), serde_json::json!(since.to_rfc3339()));
+        }
+        if let Some(until) = self.until {
+            window.insert("before_".into(), serde_json::json!(until.to_rfc3339()));
+        }
+        if !window.is_empty() {
+            logs.insert("timestamp".into(), window.into());
+        }
+        logs.into()
+    }
+}
+
+/// Prefect serves reads that take a criteria body as POSTs to `.../filter`
+/// and `.../count`; those are the only POSTs allowed in read-only mode.
+fn is_query_path(path: &str) -> bool {
+    path.ends_with("/filter") || path.ends_with("/count")
+}
+
 fn work_pool_path(name: &str) -> Result<String> {
     validate_work_pool_name(name)?;
 
@@ -335,10 +577,71 @@ mod tests {
         let config = Config {
             api_url: server.url(),
             auth_header: Some("Basic dGVzdDp0ZXN0".to_string()),
+            read_only: false,
         };
         PrefectClient::new(config)
     }
 
+    fn read_only_client(server: &mockito::Server) -> PrefectClient {
+        PrefectClient::new(Config {
+            api_url: server.url(),
+            auth_header: None,
+            read_only: true,
+        })
+    }
+
+    #[tokio::test]
+    async fn read_only_rejects_every_mutation_before_any_request() {
+        let mut server = mockito::Server::new_async().await;
+        let any = server
+            .mock("ANY", mockito::Matcher::Any)
+            .expect(0)
+            .create_async()
+            .await;
+        let client = read_only_client(&server);
+
+        let results = [
+            client
+                .create_flow_run("dep-1", serde_json::json!({}), vec![])
+                .await
+                .map(|_| ()),
+            client.cancel_flow_run("run-1", false).await.map(|_| ()),
+            client.set_deployment_paused("dep-1", true).await,
+            client
+                .set_deployment_schedule_active("dep-1", "sched-1", true)
+                .await,
+            client
+                .set_work_pool_paused("docker-prod", true)
+                .await
+                .map(|_| ()),
+        ];
+        for result in results {
+            assert!(matches!(result, Err(PfpError::ReadOnly(_))), "{:?}", result);
+        }
+        any.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn read_only_still_allows_queries() {
+        let mut server = mockito::Server::new_async().await;
+        let filter = server
+            .mock("POST", "/flow_runs/filter")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body("[]")
+            .expect(1)
+            .create_async()
+            .await;
+
+        let runs = read_only_client(&server)
+            .filter_flow_runs_global(5)
+            .await
+            .unwrap();
+
+        assert!(runs.is_empty());
+        filter.assert_async().await;
+    }
+
     #[tokio::test]
     async fn list_deployments_success() {
         let mut server = mockito::Server::new_async().await;
@@ -559,7 +862,10 @@ mod tests {
             .await;
 
         let client = test_client(&server);
-        let result = client.get_flow_run_logs("run-1", 10_000, 0).await.unwrap();
+        let result = client
+            .get_flow_run_logs(&LogFilter::for_flow_run("run-1"), 10_000, 0)
+            .await
+            .unwrap();
 
         assert_eq!(result.len(), 2);
         assert_eq!(result[0]["message"], "hello");
@@ -605,7 +911,10 @@ mod tests {
             .await;
 
         let client = test_client(&server);
-        let result = client.get_flow_run_logs("run-1", 10_000, 0).await.unwrap();
+        let result = client
+            .get_flow_run_logs(&LogFilter::for_flow_run("run-1"), 10_000, 0)
+            .await
+            .unwrap();
 
         assert_eq!(result.len(), 202);
         assert_eq!(result[0]["message"], "msg-0");
@@ -634,7 +943,10 @@ mod tests {
             .await;
 
         let client = test_client(&server);
-        let result = client.get_flow_run_logs("run-1", 150, 0).await.unwrap();
+        let result = client
+            .get_flow_run_logs(&LogFilter::for_flow_run("run-1"), 150, 0)
+            .await
+            .unwrap();
 
         assert_eq!(result.len(), 150);
         mock.assert_async().await;
@@ -657,13 +969,145 @@ mod tests {
             .await;
 
         let client = test_client(&server);
-        let result = client.get_flow_run_logs("run-1", 100, 5).await.unwrap();
+        let result = client
+            .get_flow_run_logs(&LogFilter::for_flow_run("run-1"), 100, 5)
+            .await
+            .unwrap();
 
         assert_eq!(result.len(), 1);
         assert_eq!(result[0]["message"], "new-msg");
         mock.assert_async().await;
     }
 
+    #[tokio::test]
+    async fn get_flow_run_logs_filters_by_task_run() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/logs/filter")
+            .match_body(mockito::Matcher::PartialJsonString(
+                r#"{"logs":{"flow_run_id":{"any_":["run-1"]},"task_run_id":{"any_":["task-1"]}}}"#
+                    .to_string(),
+            ))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"[{"level":40,"message":"apply failed","timestamp":"2026-01-01T00:00:00Z"}]"#,
+            )
+            .expect(1)
+            .create_async()
+            .await;
+
+        let filter = LogFilter {
+            task_run_id: Some("task-1".to_string()),
+            ..LogFilter::for_flow_run("run-1")
+        };
+        let result = test_client(&server)
+            .get_flow_run_logs(&filter, 100, 0)
+            .await
+            .unwrap();
+
+        assert_eq!(result.len(), 1);
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn get_flow_run_logs_pushes_level_and_time_window() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/logs/filter")
+            .match_body(mockito::Matcher::JsonString(
+                serde_json::json!({
+                    "logs": {
+                        "flow_run_id": {"any_": ["run-1"]},
+                        "level": {"ge_": 30},
+                        "timestamp": {
+                            "after_": "2026-03-09T12:00:00+00:00",
+                            "before_": "2026-03-10T12:00:00+00:00"
+                        }
+                    },
+                    "sort": "TIMESTAMP_ASC",
+                    "limit": 100,
+                    "offset": 0
+                })
+                .to_string(),
+            ))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body("[]")
+            .expect(1)
+            .create_async()
+            .await;
+
+        let filter = LogFilter {
+            min_level: Some(30),
+            since: Some("2026-03-09T12:00:00Z".parse().unwrap()),
+            until: Some("2026-03-10T12:00:00Z".parse().unwrap()),
+            ..LogFilter::for_flow_run("run-1")
+        };
+        test_client(&server)
+            .get_flow_run_logs(&filter, 100, 0)
+            .await
+            .unwrap();
+
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn get_flow_run_logs_tail_fetches_newest_first_and_reverses() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/logs/filter")
+            .match_body(mockito::Matcher::PartialJsonString(
+                r#"{"sort":"TIMESTAMP_DESC","limit":2,"offset":0}"#.to_string(),
+            ))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"[{"level":20,"message":"newest","timestamp":"2026-01-01T00:00:09Z"},{"level":20,"message":"older","timestamp":"2026-01-01T00:00:08Z"}]"#)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let result = test_client(&server)
+            .get_flow_run_logs_tail(&LogFilter::for_flow_run("run-1"), 2)
+            .await
+            .unwrap();
+
+        assert_eq!(result[0]["message"], "older");
+        assert_eq!(result[1]["message"], "newest");
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn filter_task_runs_for_one_flow_run() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/task_runs/filter")
+            .match_body(mockito::Matcher::JsonString(
+                serde_json::json!({
+                    "task_runs": {"flow_run_id": {"any_": ["run-1"]}},
+                    "sort": "EXPECTED_START_TIME_ASC",
+                    "limit": 200,
+                    "offset": 0
+                })
+                .to_string(),
+            ))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"[{"id":"task-1","name":"terraform_plan-0","state_type":"COMPLETED"}]"#)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let result = test_client(&server)
+            .filter_task_runs("run-1")
+            .await
+            .unwrap();
+
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0]["name"], "terraform_plan-0");
+        mock.assert_async().await;
+    }
+
     #[tokio::test]
     async fn filter_flow_runs_global_success() {
         let mut server = mockito::Server::new_async().await;
@@ -705,6 +1149,135 @@ mod tests {
         mock.assert_async().await;
     }
 
+    #[tokio::test]
+    async fn filter_flow_runs_sends_every_criterion() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/flow_runs/filter")
+            .match_body(mockito::Matcher::JsonString(
+                serde_json::json!({
+                    "flow_runs": {
+                        "deployment_id": {"any_": ["dep-1"]},
+                        "state": {"type": {"any_": ["FAILED", "CRASHED"]}},
+                        "expected_start_time": {
+                            "after_": "2026-03-09T12:00:00+00:00",
+                            "before_": "2026-03-10T12:00:00+00:00"
+                        },
+                        "tags": {"all_": ["nightly"]}
+                    },
+                    "work_pools": {"name": {"any_": ["docker-prod"]}},
+                    "sort": "START_TIME_DESC",
+                    "limit": 25,
+                    "offset": 0
+                })
+                .to_string(),
+            ))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"[{"id":"aaa-111","name":"run-1","state_type":"FAILED"}]"#)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let filter = FlowRunFilter {
+            ids: vec![],
+            deployment_id: Some("dep-1".to_string()),
+            state_types: vec!["FAILED".to_string(), "CRASHED".to_string()],
+            since: Some("2026-03-09T12:00:00Z".parse().unwrap()),
+            until: Some("2026-03-10T12:00:00Z".parse().unwrap()),
+            tags: vec!["nightly".to_string()],
+            work_pool: Some("docker-prod".to_string()),
+        };
+        let result = test_client(&server)
+            .filter_flow_runs(&filter, 25)
+            .await
+            .unwrap();
+
+        assert_eq!(result.len(), 1);
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn filter_flow_runs_follows_pagination_up_to_limit() {
+        let mut server = mockito::Server::new_async().await;
+        let page = |count: usize| -> String {
+            let runs: Vec<serde_json::Value> = (0..count)
+                .map(|i| serde_json::json!({"id": format!("run-{i}"), "name": "r"}))
+                .collect();
+            serde_json::to_string(&runs).unwrap()
+        };
+        let first = server
+            .mock("POST", "/flow_runs/filter")
+            .match_body(mockito::Matcher::JsonString(
+                serde_json::json!({"sort": "START_TIME_DESC", "limit": 200, "offset": 0})
+                    .to_string(),
+            ))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(page(200))
+            .expect(1)
+            .create_async()
+            .await;
+        let second = server
+            .mock("POST", "/flow_runs/filter")
+            .match_body(mockito::Matcher::JsonString(
+                serde_json::json!({"sort": "START_TIME_DESC", "limit": 50, "offset": 200})
+                    .to_string(),
+            ))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(page(50))
+            .expect(1)
+            .create_async()
+            .await;
+
+        let result = test_client(&server)
+            .filter_flow_runs(&FlowRunFilter::default(), 250)
+            .await
+            .unwrap();
+
+        assert_eq!(result.len(), 250);
+        first.assert_async().await;
+        second.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn lists_every_work_pool_page() {
+        let mut server = mockito::Server::new_async().await;
+        let first_page: Vec<serde_json::Value> = (0..100)
+            .map(|index| serde_json::json!({"name": format!("pool-{index}"), "is_paused": false}))
+            .collect();
+        let first = server
+            .mock("POST", "/work_pools/filter")
+            .match_body(mockito::Matcher::JsonString(
+                serde_json::json!({"limit": 100, "offset": 0}).to_string(),
+            ))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(serde_json::to_string(&first_page).unwrap())
+            .expect(1)
+            .create_async()
+            .await;
+        let second = server
+            .mock("POST", "/work_pools/filter")
+            .match_body(mockito::Matcher::JsonString(
+                serde_json::json!({"limit": 100, "offset": 100}).to_string(),
+            ))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"[{"name":"pool-100","type":"docker","is_paused":true}]"#)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let pools = test_client(&server).list_work_pools().await.unwrap();
+
+        assert_eq!(pools.len(), 101);
+        assert!(pools[100].is_paused);
+        first.assert_async().await;
+        second.assert_async().await;
+    }
+
     #[tokio::test]
     async fn gets_exact_work_pool() {
         let mut server = mockito::Server::new_async().await;
@@ -870,6 +1443,75 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn cancel_proposes_cancelling_without_force() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/flow_runs/run-1/set_state")
+            .match_body(mockito::Matcher::PartialJsonString(
+                r#"{"state":{"type":"CANCELLING"},"force":false}"#.to_string(),
+            ))
+            .with_status(201)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"status":"ACCEPT","state":{"type":"CANCELLING","name":"Cancelling"}}"#)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let result = test_client(&server)
+            .cancel_flow_run("run-1", false)
+            .await
+            .unwrap();
+
+        assert_eq!(result.state_name(), Some("Cancelling"));
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn forced_cancel_sets_cancelled() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/flow_runs/run-1/set_state")
+            .match_body(mockito::Matcher::PartialJsonString(
+                r#"{"state":{"type":"CANCELLED"},"force":true}"#.to_string(),
+            ))
+            .with_status(201)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"status":"ACCEPT","state":{"type":"CANCELLED","name":"Cancelled"}}"#)
+            .expect(1)
+            .create_async()
+            .await;
+
+        test_client(&server)
+            .cancel_flow_run("run-1", true)
+            .await
+            .unwrap();
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn rejected_cancel_is_an_error() {
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("POST", "/flow_runs/run-1/set_state")
+            .with_status(201)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{"status":"REJECT","details":{"reason":"This run is already in a terminal state."}}"#,
+            )
+            .create_async()
+            .await;
+
+        let err = test_client(&server)
+            .cancel_flow_run("run-1", false)
+            .await
+            .unwrap_err();
+
+        assert!(
+            matches!(err, PfpError::NotAccepted(ref msg) if msg.contains("already in a terminal state"))
+        );
+    }
+
     #[tokio::test]
     async fn resume_fails_if_verification_response_omits_pause_state() {
         let mut server = mockito::Server::new_async().await;
@@ -1,10 +1,865 @@
 use crate::client::PrefectClient;
-use crate::error::Result;
+use crate::commands::runs::RunFilterArgs;
+use crate::error::{PfpError, Result};
+use crate::guard::Guard;
+use crate::logger;
+use crate::models::{Deployment, FlowRun, FlowRunDetail};
+use crate::output;
 use crate::resolve;
+use crate::timefmt::TimeDisplay;
+use crate::timespec;
+use std::collections::{BTreeSet, HashMap};
+use std::sync::Arc;
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
 
-pub async fn run(client: PrefectClient, flow_run_id: String) -> Result<()> {
-    let resolved_id = resolve::resolve_flow_run(&client, &flow_run_id).await?;
-    client.cancel_flow_run(&resolved_id).await?;
-    eprintln!("Cancelled flow run: {}", resolved_id);
+/// States a bulk cancel selects when `--state` is not given.
+const CANCELLABLE_STATES: &[&str] = &["SCHEDULED", "PENDING", "RUNNING", "PAUSED"];
+
+const WAIT_POLL_SECS: u64 = 2;
+
+/// How a cancel is requested and whether to wait for it to finish.
+#[derive(Debug, clap::Args)]
+pub struct CancelModeArgs {
+    /// Set CANCELLED directly, skipping Prefect's infrastructure teardown
+    #[arg(long)]
+    pub force: bool,
+    /// Wait until each run reaches a terminal state
+    #[arg(long)]
+    pub wait: bool,
+    /// Give up waiting after this long (e.g. 90s, 5m)
+    #[arg(long, default_value = "5m", requires = "wait")]
+    pub timeout: String,
+}
+
+impl Default for CancelModeArgs {
+    fn default() -> Self {
+        Self {
+            force: false,
+            wait: false,
+            timeout: "5m".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct CancelMode {
+    force: bool,
+    wait: Option<std::time::Duration>,
+}
+
+impl CancelModeArgs {
+    fn to_mode(&self) -> Result<CancelMode> {
+        let wait = if self.wait {
+            let timeout = timespec::parse_duration(&self.timeout)
+                .map_err(PfpError::Validation)?
+                .to_std()
+                .map_err(|e| PfpError::Validation(e.to_string()))?;
+            Some(timeout)
+        } else {
+            None
+        };
+        Ok(CancelMode {
+            force: self.force,
+            wait,
+        })
+    }
+}
+
+/// Flags selecting many flow runs to cancel at once.
+#[derive(Debug, Default, clap::Args)]
+pub struct BulkCancelArgs {
+    /// Cancel runs of this deployment (substring match)
+    #[arg(long)]
+    pub deployment: Option<String>,
+    #[command(flatten)]
+    pub filter: RunFilterArgs,
+    /// Maximum number of flow runs to select
+    #[arg(long, default_value_t = 100)]
+    pub limit: usize,
+    /// Number of cancel requests in flight at once
+    #[arg(long, default_value_t = 4, value_parser = clap::value_parser!(u16).range(1..))]
+    pub concurrency: u16,
+}
+
+impl BulkCancelArgs {
+    fn has_selector(&self) -> bool {
+        let f = &self.filter;
+        self.deployment.is_some()
+            || !f.states.is_empty()
+            || f.since.is_some()
+            || f.until.is_some()
+            || !f.tags.is_empty()
+            || f.work_pool.is_some()
+    }
+}
+
+pub async fn run(
+    client: PrefectClient,
+    flow_run_id: Option<String>,
+    mode: CancelModeArgs,
+    bulk: BulkCancelArgs,
+    guard: &Guard,
+    time: TimeDisplay,
+) -> Result<()> {
+    let mode = mode.to_mode()?;
+    match flow_run_id {
+        Some(flow_run_id) => {
+            let resolved_id = resolve::resolve_flow_run(&client, &flow_run_id).await?;
+            let detail: FlowRunDetail =
+                serde_json::from_value(client.get_flow_run(&resolved_id).await?)
+                    .map_err(|e| PfpError::Api(e.to_string()))?;
+            let runs = std::slice::from_ref(&detail);
+            let names = deployment_names(&client, guard).await?;
+            check_policy(guard, runs, &names)?;
+            guard.confirm(
+                &format!(
+                    "Cancel flow run {} ({})",
+                    detail.run.name,
+                    detail.run.short_id()
+                ),
+                &protected_targets(guard, runs, &names),
+            )?;
+            let outcome = cancel_one(&client, &resolved_id, mode).await?;
+            logger::record_result("final_state", outcome.state());
+            eprintln!("Flow run {}: {}", resolved_id, outcome);
+            Ok(())
+        }
+        None => run_bulk(client, mode, bulk, guard, time).await,
+    }
+}
+
+/// Full deployment names by deployment ID. Only looked up when protection
+/// rules or a cancel policy need them; empty otherwise.
+async fn deployment_names(
+    client: &PrefectClient,
+    guard: &Guard,
+) -> Result<HashMap<String, String>> {
+    if !guard.protects_anything() && !guard.policy().restricts_cancel() {
+        return Ok(HashMap::new());
+    }
+    Ok(client
+        .list_deployments()
+        .await?
+        .into_iter()
+        .filter_map(|v| serde_json::from_value::<Deployment>(v).ok())
+        .map(|d| (d.id.clone(), d.full_name()))
+        .collect())
+}
+
+fn deployment_name<'a>(
+    detail: &FlowRunDetail,
+    names: &'a HashMap<String, String>,
+) -> Option<&'a str> {
+    let id = detail.run.deployment_id.as_ref()?;
+    names.get(id).map(String::as_str)
+}
+
+fn check_policy(
+    guard: &Guard,
+    runs: &[FlowRunDetail],
+    names: &HashMap<String, String>,
+) -> Result<()> {
+    guard
+        .policy()
+        .check_cancel(runs.iter().map(|detail| deployment_name(detail, names)))
+}
+
+/// Protected deployments and work pools among `runs`.
+fn protected_targets(
+    guard: &Guard,
+    runs: &[FlowRunDetail],
+    names: &HashMap<String, String>,
+) -> Vec<String> {
+    let mut protected = BTreeSet::new();
+    for detail in runs {
+        if let Some(name) =
+            deployment_name(detail, names).filter(|name| guard.is_protected_deployment(name))
+        {
+            protected.insert(name.to_string());
+        }
+        if let Some(pool) = detail
+            .work_pool_name
+            .as_ref()
+            .filter(|pool| guard.is_protected_pool(pool))
+        {
+            protected.insert(pool.clone());
+        }
+    }
+    protected.into_iter().collect()
+}
+
+/// What a cancel request achieved.
+#[derive(Debug, Clone, PartialEq)]
+enum CancelOutcome {
+    /// Cancellation accepted; the last state name observed
+    Cancelled(String),
+    /// The run reached this end state on its own before the cancel landed
+    AlreadyFinished(String),
+}
+
+impl CancelOutcome {
+    fn state(&self) -> &str {
+        match self {
+            CancelOutcome::Cancelled(state) | CancelOutcome::AlreadyFinished(state) => state,
+        }
+    }
+}
+
+impl std::fmt::Display for CancelOutcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CancelOutcome::Cancelled(state) => f.write_str(state),
+            CancelOutcome::AlreadyFinished(state) => write!(f, "already finished ({})", state),
+        }
+    }
+}
+
+async fn fetch_run(client: &PrefectClient, flow_run_id: &str) -> Result<FlowRun> {
+    serde_json::from_value(client.get_flow_run(flow_run_id).await?)
+        .map_err(|e| PfpError::Api(e.to_string()))
+}
+
+/// Request cancellation and, in wait mode, poll until the run is terminal.
+/// A run that ends in any state other than Cancelled, or whose cancel is
+/// refused because it already ended, is reported as already finished.
+async fn cancel_one(
+    client: &PrefectClient,
+    flow_run_id: &str,
+    mode: CancelMode,
+) -> Result<CancelOutcome> {
+    let result = match client.cancel_flow_run(flow_run_id, mode.force).await {
+        Ok(result) => result,
+        // Only a refused proposal may mean the run ended first; read-only
+        // mode, policy and transport errors are reported as they are.
+        Err(e @ PfpError::NotAccepted(_)) => {
+            return match fetch_run(client, flow_run_id).await {
+                Ok(run) if run.is_terminal() => Ok(CancelOutcome::AlreadyFinished(run.state_name)),
+                _ => Err(e),
+            };
+        }
+        Err(e) => return Err(e),
+    };
+    let state = result
+        .state_name()
+        .unwrap_or(if mode.force {
+            "Cancelled"
+        } else {
+            "Cancelling"
+        })
+        .to_string();
+    let Some(timeout) = mode.wait else {
+        return Ok(CancelOutcome::Cancelled(state));
+    };
+
+    let deadline = tokio::time::Instant::now() + timeout;
+    loop {
+        let run = fetch_run(client, flow_run_id).await?;
+        if run.is_terminal() {
+            return Ok(if run.state_type == "CANCELLED" {
+                CancelOutcome::Cancelled(run.state_name)
+            } else {
+                CancelOutcome::AlreadyFinished(run.state_name)
+            });
+        }
+        if tokio::time::Instant::now() >= deadline {
+            return Err(PfpError::Timeout(format!(
+                "flow run {} still {} after waiting for cancellation",
+                run.short_id(),
+                run.state_name
+            )));
+        }
+        tokio::time::sleep(std::time::Duration::from_secs(WAIT_POLL_SECS)).await;
+    }
+}
+
+async fn run_bulk(
+    client: PrefectClient,
+    mode: CancelMode,
+    bulk: BulkCancelArgs,
+    guard: &Guard,
+    time: TimeDisplay,
+) -> Result<()> {
+    if !bulk.has_selector() {
+        return Err(PfpError::Validation(
+            "give a flow run ID, or select runs with --deployment, --state, --since, --until, --tag or --work-pool"
+                .to_string(),
+        ));
+    }
+    let mut filter = bulk.filter.to_filter()?;
+    if filter.state_types.is_empty() {
+        filter.state_types = CANCELLABLE_STATES.iter().map(|s| s.to_string()).collect();
+    }
+    if let Some(query) = &bulk.deployment {
+        let deployment = resolve::resolve_deployment(&client, query).await?;
+        filter.deployment_id = Some(deployment.id);
+    }
+
+    let details: Vec<FlowRunDetail> = client
+        .filter_flow_runs(&filter, bulk.limit)
+        .await?
+        .into_iter()
+        .filter_map(|v| serde_json::from_value(v).ok())
+        .collect();
+    if details.is_empty() {
+        println!("No matching flow runs to cancel");
+        return Ok(());
+    }
+
+    let names = deployment_names(&client, guard).await?;
+    check_policy(guard, &details, &names)?;
+    let protected = protected_targets(guard, &details, &names);
+    let runs: Vec<FlowRun> = details.into_iter().map(|d| d.run).collect();
+    output::print_flow_runs_table(&runs, false, time);
+    guard.confirm_bulk(&format!("Cancel {} flow run(s)", runs.len()), &protected)?;
+
+    let results = cancel_all(client, &runs, mode, bulk.concurrency as usize).await;
+    let outcomes: serde_json::Map<String, serde_json::Value> = runs
+        .iter()
+        .zip(&results)
+        .map(|(run, result)| {
+            let state = result.as_ref().map_or("Failed", CancelOutcome::state);
+            (run.id.clone(), state.into())
+        })
+        .collect();
+    logger::record_result("flow_runs", outcomes);
+    let failed = print_summary(&runs, &results);
+    if failed > 0 {
+        return Err(PfpError::PartialFailure(format!(
+            "{} of {} cancellations failed",
+            failed,
+            runs.len()
+        )));
+    }
     Ok(())
 }
+
+/// Cancel every run with at most `concurrency` requests in flight. Results
+/// are returned in the order of `runs`.
+async fn cancel_all(
+    client: PrefectClient,
+    runs: &[FlowRun],
+    mode: CancelMode,
+    concurrency: usize,
+) -> Vec<Result<CancelOutcome>> {
+    let client = Arc::new(client);
+    let permits = Arc::new(Semaphore::new(concurrency));
+    let mut tasks = JoinSet::new();
+    for (index, run) in runs.iter().enumerate() {
+        let client = Arc::clone(&client);
+        let permits = Arc::clone(&permits);
+        let id = run.id.clone();
+        tasks.spawn(async move {
+            let _permit = permits
+                .acquire_owned()
+                .await
+                .expect("semaphore never closed");
+            (index, cancel_one(&client, &id, mode).await)
+        });
+    }
+
+    let mut results: Vec<Option<Result<CancelOutcome>>> = runs.iter().map(|_| None).collect();
+    while let Some(joined) = tasks.join_next().await {
+        match joined {
+            Ok((index, result)) => results[index] = Some(result),
+            Err(e) => eprintln!("Warning: cancel task panicked: {}", e),
+        }
+    }
+    results
+        .into_iter()
+        .map(|r| r.unwrap_or_else(|| Err(PfpError::Api("cancel task did not finish".into()))))
+        .collect()
+}
+
+/// Print one result line per run and return the number of failures.
+fn print_summary(runs: &[FlowRun], results: &[Result<CancelOutcome>]) -> usize {
+    let (mut failed, mut finished) = (0, 0);
+    for (run, result) in runs.iter().zip(results) {
+        match result {
+            Ok(CancelOutcome::AlreadyFinished(_)) => finished += 1,
+            Ok(_) => {}
+            Err(_) => failed += 1,
+        }
+        eprintln!("{}", summary_line(run, result));
+    }
+    eprintln!(
+        "{} requested, {} already finished, {} failed",
+        runs.len() - failed - finished,
+        finished,
+        failed
+    );
+    failed
+}
+
+/// `short_id name: outcome`, the same shape for every outcome.
+fn summary_line(run: &FlowRun, result: &Result<CancelOutcome>) -> String {
+    let outcome = match result {
+        Ok(outcome) => outcome.to_string(),
+        Err(e) => format!("failed ({})", e),
+    };
+    format!("{} {}: {}", run.short_id(), run.name, outcome)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+
+    fn test_client(server: &mockito::Server) -> PrefectClient {
+        PrefectClient::new(Config {
+            api_url: server.url(),
+            auth_header: Some("Basic dGVzdDp0ZXN0".to_string()),
+            read_only: false,
+        })
+    }
+
+    fn bulk() -> BulkCancelArgs {
+        BulkCancelArgs {
+            filter: RunFilterArgs {
+                work_pool: Some("docker-prod".to_string()),
+                ..Default::default()
+            },
+            limit: 100,
+            concurrency: 2,
+            ..Default::default()
+        }
+    }
+
+    fn guard(yes: bool) -> Guard {
+        let confirm = crate::guard::ConfirmArgs { yes, i_know: false };
+        Guard::new(confirm, Default::default(), false)
+    }
+
+    async fn mock_selected_runs(server: &mut mockito::Server) -> mockito::Mock {
+        server
+            .mock("POST", "/flow_runs/filter")
+            .match_body(mockito::Matcher::PartialJsonString(
+                r#"{"flow_runs":{"state":{"type":{"any_":["SCHEDULED","PENDING","RUNNING","PAUSED"]}}},"work_pools":{"name":{"any_":["docker-prod"]}}}"#
+                    .to_string(),
+            ))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"[{"id":"run-1","name":"one","state_type":"RUNNING"},
+                    {"id":"run-2","name":"two","state_type":"PENDING"},
+                    {"id":"run-3","name":"three","state_type":"SCHEDULED"}]"#,
+            )
+            .expect(1)
+            .create_async()
+            .await
+    }
+
+    #[tokio::test]
+    async fn wait_reports_final_observed_state() {
+        let mut server = mockito::Server::new_async().await;
+        let flow_run_id = "aaaaaaaa-bbbb-cccc-dddd-eeeeeeeeeeee";
+        let cancel = server
+            .mock(
+                "POST",
+                format!("/flow_runs/{}/set_state", flow_run_id).as_str(),
+            )
+            .with_status(201)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"status":"ACCEPT","state":{"type":"CANCELLING","name":"Cancelling"}}"#)
+            .expect(1)
+            .create_async()
+            .await;
+        let poll = server
+            .mock("GET", format!("/flow_runs/{}", flow_run_id).as_str())
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(format!(
+                r#"{{"id":"{}","name":"r","state_type":"CANCELLED","state_name":"Cancelled"}}"#,
+                flow_run_id
+            ))
+            .expect(1)
+            .create_async()
+            .await;
+
+        let mode = CancelMode {
+            force: false,
+            wait: Some(std::time::Duration::from_secs(60)),
+        };
+        let outcome = cancel_one(&test_client(&server), flow_run_id, mode)
+            .await
+            .unwrap();
+
+        assert_eq!(outcome, CancelOutcome::Cancelled("Cancelled".to_string()));
+        cancel.assert_async().await;
+        poll.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn wait_reports_run_that_completed_first_as_already_finished() {
+        let mut server = mockito::Server::new_async().await;
+        let flow_run_id = "aaaaaaaa-bbbb-cccc-dddd-eeeeeeeeeeee";
+        server
+            .mock(
+                "POST",
+                format!("/flow_runs/{}/set_state", flow_run_id).as_str(),
+            )
+            .with_status(201)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"status":"ACCEPT","state":{"type":"CANCELLING","name":"Cancelling"}}"#)
+            .create_async()
+            .await;
+        server
+            .mock("GET", format!("/flow_runs/{}", flow_run_id).as_str())
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(format!(
+                r#"{{"id":"{}","name":"r","state_type":"COMPLETED","state_name":"Completed"}}"#,
+                flow_run_id
+            ))
+            .create_async()
+            .await;
+
+        let mode = CancelMode {
+            force: false,
+            wait: Some(std::time::Duration::from_secs(60)),
+        };
+        let outcome = cancel_one(&test_client(&server), flow_run_id, mode)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            outcome,
+            CancelOutcome::AlreadyFinished("Completed".to_string())
+        );
+        assert_eq!(outcome.to_string(), "already finished (Completed)");
+    }
+
+    #[tokio::test]
+    async fn rejected_cancel_of_ended_run_is_already_finished() {
+        let mut server = mockito::Server::new_async().await;
+        let flow_run_id = "aaaaaaaa-bbbb-cccc-dddd-eeeeeeeeeeee";
+        server
+            .mock(
+                "POST",
+                format!("/flow_runs/{}/set_state", flow_run_id).as_str(),
+            )
+            .with_status(201)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{"status":"REJECT","details":{"reason":"This run is already in a terminal state."}}"#,
+            )
+            .create_async()
+            .await;
+        server
+            .mock("GET", format!("/flow_runs/{}", flow_run_id).as_str())
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(format!(
+                r#"{{"id":"{}","name":"r","state_type":"FAILED","state_name":"Failed"}}"#,
+                flow_run_id
+            ))
+            .create_async()
+            .await;
+
+        let outcome = cancel_one(
+            &test_client(&server),
+            flow_run_id,
+            CancelModeArgs::default().to_mode().unwrap(),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(
+            outcome,
+            CancelOutcome::AlreadyFinished("Failed".to_string())
+        );
+    }
+
+    #[tokio::test]
+    async fn read_only_cancel_of_ended_run_is_refused() {
+        let mut server = mockito::Server::new_async().await;
+        let flow_run_id = "aaaaaaaa-bbbb-cccc-dddd-eeeeeeeeeeee";
+        let lookup = server
+            .mock("GET", format!("/flow_runs/{}", flow_run_id).as_str())
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(format!(
+                r#"{{"id":"{}","name":"r","state_type":"COMPLETED","state_name":"Completed"}}"#,
+                flow_run_id
+            ))
+            .expect(0)
+            .create_async()
+            .await;
+        let client = PrefectClient::new(Config {
+            api_url: server.url(),
+            auth_header: None,
+            read_only: true,
+        });
+
+        let err = cancel_one(
+            &client,
+            flow_run_id,
+            CancelModeArgs::default().to_mode().unwrap(),
+        )
+        .await
+        .unwrap_err();
+
+        assert!(matches!(err, PfpError::ReadOnly(_)), "{err:?}");
+        assert_eq!(err.exit_code(), 3);
+        lookup.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn server_error_on_cancel_is_not_reclassified() {
+        let mut server = mockito::Server::new_async().await;
+        let flow_run_id = "aaaaaaaa-bbbb-cccc-dddd-eeeeeeeeeeee";
+        server
+            .mock(
+                "POST",
+                format!("/flow_runs/{}/set_state", flow_run_id).as_str(),
+            )
+            .with_status(500)
+            .create_async()
+            .await;
+        let lookup = server
+            .mock("GET", format!("/flow_runs/{}", flow_run_id).as_str())
+            .expect(0)
+            .create_async()
+            .await;
+
+        let err = cancel_one(
+            &test_client(&server),
+            flow_run_id,
+            CancelModeArgs::default().to_mode().unwrap(),
+        )
+        .await
+        .unwrap_err();
+
+        assert!(
+            matches!(err, PfpError::Api(ref msg) if msg.contains("500")),
+            "{err:?}"
+        );
+        lookup.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn wait_times_out_while_still_cancelling() {
+        let mut server = mockito::Server::new_async().await;
+        let flow_run_id = "aaaaaaaa-bbbb-cccc-dddd-eeeeeeeeeeee";
+        server
+            .mock(
+                "POST",
+                format!("/flow_runs/{}/set_state", flow_run_id).as_str(),
+            )
+            .with_status(201)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"status":"ACCEPT"}"#)
+            .create_async()
+            .await;
+        server
+            .mock("GET", format!("/flow_runs/{}", flow_run_id).as_str())
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(format!(
+                r#"{{"id":"{}","name":"r","state_type":"CANCELLING","state_name":"Cancelling"}}"#,
+                flow_run_id
+            ))
+            .create_async()
+            .await;
+
+        let mode = CancelMode {
+            force: false,
+            wait: Some(std::time::Duration::ZERO),
+        };
+        let err = cancel_one(&test_client(&server), flow_run_id, mode)
+            .await
+            .unwrap_err();
+
+        assert!(matches!(err, PfpError::Timeout(ref msg) if msg.contains("Cancelling")));
+    }
+
+    #[tokio::test]
+    async fn bulk_cancel_requires_a_selector() {
+        let server = mockito::Server::new_async().await;
+        let err = run(
+            test_client(&server),
+            None,
+            CancelModeArgs::default(),
+            BulkCancelArgs::default(),
+            &guard(false),
+            TimeDisplay::default(),
+        )
+        .await
+        .unwrap_err();
+        assert!(matches!(err, PfpError::Validation(_)));
+    }
+
+    #[tokio::test]
+    async fn bulk_cancel_without_yes_only_previews() {
+        let mut server = mockito::Server::new_async().await;
+        let select = mock_selected_runs(&mut server).await;
+        let cancel = server
+            .mock("POST", mockito::Matcher::Regex("/set_state$".to_string()))
+            .expect(0)
+            .create_async()
+            .await;
+
+        let err = run(
+            test_client(&server),
+            None,
+            CancelModeArgs::default(),
+            bulk(),
+            &guard(false),
+            TimeDisplay::default(),
+        )
+        .await
+        .unwrap_err();
+
+        assert!(matches!(err, PfpError::Validation(ref msg) if msg.contains("--yes")));
+        select.assert_async().await;
+        cancel.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn bulk_cancel_of_protected_pool_needs_i_know() {
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("POST", "/flow_runs/filter")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"[{"id":"run-1","name":"one","state_type":"RUNNING","work_pool_name":"docker-prod"}]"#,
+            )
+            .create_async()
+            .await;
+        server
+            .mock("POST", "/deployments/filter")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body("[]")
+            .create_async()
+            .await;
+        let cancel = server
+            .mock("POST", mockito::Matcher::Regex("/set_state$".to_string()))
+            .expect(0)
+            .create_async()
+            .await;
+
+        let protect = crate::settings::Protection {
+            deployments: vec![],
+            pools: vec!["*-prod".to_string()],
+        };
+        let confirm = crate::guard::ConfirmArgs {
+            yes: true,
+            i_know: false,
+        };
+        let err = run(
+            test_client(&server),
+            None,
+            CancelModeArgs::default(),
+            bulk(),
+            &Guard::new(confirm, protect, false),
+            TimeDisplay::default(),
+        )
+        .await
+        .unwrap_err();
+
+        assert!(matches!(err, PfpError::Validation(ref msg) if msg.contains("docker-prod")));
+        cancel.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn bulk_cancel_outside_policy_is_denied() {
+        let mut server = mockito::Server::new_async().await;
+        mock_selected_runs(&mut server).await;
+        server
+            .mock("POST", "/deployments/filter")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body("[]")
+            .create_async()
+            .await;
+        let cancel = server
+            .mock("POST", mockito::Matcher::Regex("/set_state$".to_string()))
+            .expect(0)
+            .create_async()
+            .await;
+
+        let policy: crate::policy::Policy =
+            toml::from_str("[cancel]\ndeployments = [\"*-dev\"]\n").unwrap();
+        let err = run(
+            test_client(&server),
+            None,
+            CancelModeArgs::default(),
+            bulk(),
+            &guard(true).with_policy(policy),
+            TimeDisplay::default(),
+        )
+        .await
+        .unwrap_err();
+
+        assert!(
+            matches!(err, PfpError::PolicyDenied(ref msg) if msg.contains("without a deployment"))
+        );
+        cancel.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn bulk_cancel_reports_partial_failure() {
+        let mut server = mockito::Server::new_async().await;
+        mock_selected_runs(&mut server).await;
+        let ok = server
+            .mock(
+                "POST",
+                mockito::Matcher::Regex("^/flow_runs/run-[13]/set_state$".to_string()),
+            )
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"status":"ACCEPT","state":{"type":"CANCELLING","name":"Cancelling"}}"#)
+            .expect(2)
+            .create_async()
+            .await;
+        let failing = server
+            .mock("POST", "/flow_runs/run-2/set_state")
+            .with_status(500)
+            .with_body("boom")
+            .expect(1)
+            .create_async()
+            .await;
+
+        let err = run(
+            test_client(&server),
+            None,
+            CancelModeArgs::default(),
+            bulk(),
+            &guard(true),
+            TimeDisplay::default(),
+        )
+        .await
+        .unwrap_err();
+
+        assert!(matches!(err, PfpError::PartialFailure(ref msg) if msg.contains("1 of 3")));
+        assert_eq!(err.exit_code(), 4);
+        ok.assert_async().await;
+        failing.assert_async().await;
+    }
+
+    #[test]
+    fn summary_lines_share_one_layout() {
+        let run: FlowRun = serde_json::from_value(serde_json::json!({
+            "id": "aaaaaaaa-bbbb", "name": "nightly", "state_type": "RUNNING", "state_name": "Running"
+        }))
+        .unwrap();
+        let lines: Vec<String> = [
+            Ok(CancelOutcome::Cancelled("Cancelling".to_string())),
+            Ok(CancelOutcome::AlreadyFinished("Completed".to_string())),
+            Err(PfpError::Api("500: boom".to_string())),
+        ]
+        .iter()
+        .map(|result| summary_line(&run, result))
+        .collect();
+        assert_eq!(
+            lines,
+            vec![
+                "aaaaaaaa nightly: Cancelling",
+                "aaaaaaaa nightly: already finished (Completed)",
+                "aaaaaaaa nightly: failed (API error: 500: boom)",
+            ]
+        );
+    }
+}
@@ -0,0 +1,364 @@
+use crate::error::{PfpError, Result};
+use crate::logger::{self, LoggedInvocation};
+use crate::output::{self, OutputFormat};
+use crate::timefmt::{self, TimeDisplay};
+use crate::timespec;
+use chrono::{DateTime, Utc};
+use colored::Colorize;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+/// Record selection flags for `pfp history`.
+#[derive(Debug, Default, clap::Args)]
+pub struct HistoryFilterArgs {
+    /// Only this command, e.g. run or pool (matches "pool pause" too)
+    #[arg(long)]
+    pub command: Option<String>,
+    /// Only invocations at or after this time (e.g. 24h, 2026-03-01, RFC 3339)
+    #[arg(long)]
+    pub since: Option<String>,
+    /// Only invocations that failed or were denied
+    #[arg(long)]
+    pub errors: bool,
+    /// Only invocations whose arguments mention this text (case-insensitive)
+    #[arg(long)]
+    pub query: Option<String>,
+}
+
+/// Per-command totals for `--summary`.
+#[derive(Debug, Serialize, PartialEq)]
+pub struct CommandSummary {
+    pub command: String,
+    pub count: usize,
+    pub errors: usize,
+    pub p50_ms: u64,
+    pub p95_ms: u64,
+}
+
+pub fn run(
+    filter: HistoryFilterArgs,
+    limit: usize,
+    summary: bool,
+    format: OutputFormat,
+    time: TimeDisplay,
+) -> Result<()> {
+    let since = filter
+        .since
+        .as_deref()
+        .map(|s| timespec::parse_time_bound(s, Utc::now()))
+        .transpose()
+        .map_err(PfpError::Validation)?;
+
+    let records: Vec<LoggedInvocation> = logger::read_history()
+        .into_iter()
+        .filter(|record| matches(record, &filter, since))
+        .collect();
+
+    if summary {
+        let summaries = summarize(&records);
+        match format {
+            OutputFormat::Json => output::print_json(&summaries),
+            OutputFormat::Jsonl => output::print_json_lines(&summaries),
+            OutputFormat::Table => print_summary(&summaries),
+        }
+        return Ok(());
+    }
+
+    let newest: Vec<LoggedInvocation> = records.into_iter().rev().take(limit).collect();
+    match format {
+        OutputFormat::Json => output::print_json(&newest),
+        OutputFormat::Jsonl => output::print_json_lines(&newest),
+        OutputFormat::Table => print_table(&newest, time),
+    }
+    Ok(())
+}
+
+fn matches(
+    record: &LoggedInvocation,
+    filter: &HistoryFilterArgs,
+    since: Option<DateTime<Utc>>,
+) -> bool {
+    if let Some(command) = &filter.command {
+        let under = record
+            .command
+            .strip_prefix(command.as_str())
+            .is_some_and(|rest| rest.is_empty() || rest.starts_with(' '));
+        if !under {
+            return false;
+        }
+    }
+    if filter.errors && record.is_ok() {
+        return false;
+    }
+    if let Some(since) = since {
+        match timefmt::parse_timestamp(&record.ts) {
+            Some(ts) if ts >= since => {}
+            _ => return false,
+        }
+    }
+    if let Some(query) = &filter.query {
+        if !mentions(&record.args, &query.to_lowercase()) {
+            return false;
+        }
+    }
+    true
+}
+
+/// Whether any string in `value` contains `needle` (already lowercased).
+fn mentions(value: &Value, needle: &str) -> bool {
+    match value {
+        Value::String(s) => s.to_lowercase().contains(needle),
+        Value::Array(items) => items.iter().any(|item| mentions(item, needle)),
+        Value::Object(map) => map.values().any(|item| mentions(item, needle)),
+        _ => false,
+    }
+}
+
+fn summarize(records: &[LoggedInvocation]) -> Vec<CommandSummary> {
+    let mut by_command: BTreeMap<&str, Vec<&LoggedInvocation>> = BTreeMap::new();
+    for record in records {
+        by_command.entry(&record.command).or_default().push(record);
+    }
+    by_command
+        .into_iter()
+        .map(|(command, records)| {
+            let mut durations: Vec<u64> = records.iter().map(|r| r.duration_ms).collect();
+            durations.sort_unstable();
+            CommandSummary {
+                command: command.to_string(),
+                count: records.len(),
+                errors: records.iter().filter(|r| !r.is_ok()).count(),
+                p50_ms: percentile(&durations, 50),
+                p95_ms: percentile(&durations, 95),
+            }
+        })
+        .collect()
+}
+
+fn print_table(records: &[LoggedInvocation], time: TimeDisplay) {
+    println!(
+        "{:<20} {:<18} {:<8} {:>9}  ARGS",
+        "TIME", "COMMAND", "OUTCOME", "DURATION"
+    );
+    for record in records {
+        let outcome = match record.outcome.as_str() {
+            "ok" => record.outcome.green(),
+            "denied" => record.outcome.yellow(),
+            _ => record.outcome.red(),
+        };
+        println!(
+            "{:<20} {:<18} {:<8} {:>9}  {}",
+            time.table_time(Some(&record.ts)),
+            record.command,
+            outcome,
+            format_ms(record.duration_ms),
+            output::truncate(&args_summary(&record.args), 60),
+        );
+        if let Some(error) = &record.error {
+            println!("  {}", error.red());
+        }
+    }
+}
+
+fn print_summary(summaries: &[CommandSummary]) {
+    println!(
+        "{:<18} {:>6} {:>6} {:>9} {:>9}",
+        "COMMAND", "RUNS", "ERRORS", "P50", "P95"
+    );
+    for s in summaries {
+        println!(
+            "{:<18} {:>6} {:>6} {:>9} {:>9}",
+            s.command,
+            s.count,
+            s.errors,
+            format_ms(s.p50_ms),
+            format_ms(s.p95_ms),
+        );
+    }
+}
+
+/// Logged arguments as a compact line: positional values first, then
+/// `key=value` for options that were given and bare `key` for flags.
+fn args_summary(args: &Value) -> String {
+    const POSITIONAL: &[&str] = &["query", "flow_run_id", "flow_run_ids", "name"];
+    let Value::Object(map) = args else {
+        return String::new();
+    };
+    let text = |value: &Value| match value {
+        Value::String(s) => s.clone(),
+        Value::Array(items) => items
+            .iter()
+            .map(|item| {
+                item.as_str()
+                    .map_or_else(|| item.to_string(), str::to_string)
+            })
+            .collect::<Vec<_>>()
+            .join(","),
+        other => other.to_string(),
+    };
+    let given = |value: &Value| match value {
+        Value::Null | Value::Bool(false) => false,
+        Value::String(s) => !s.is_empty(),
+        Value::Array(items) => !items.is_empty(),
+        _ => true,
+    };
+
+    let positional = POSITIONAL
+        .iter()
+        .filter_map(|key| map.get(*key).filter(|v| given(v)).map(text));
+    let options = map
+        .iter()
+        .filter(|(key, value)| !POSITIONAL.contains(&key.as_str()) && given(value))
+        .map(|(key, value)| match value {
+            Value::Bool(true) => key.clone(),
+            other => format!("{}={}", key, text(other)),
+        });
+    positional.chain(options).collect::<Vec<_>>().join(" ")
+}
+
+/// A duration in milliseconds, e.g. `850ms`, `2.4s`, `3m 05s`.
+fn format_ms(ms: u64) -> String {
+    if ms < 1000 {
+        format!("{}ms", ms)
+    } else if ms < 60_000 {
+        format!("{:.1}s", ms as f64 / 1000.0)
+    } else {
+        format!("{}m {:02}s", ms / 60_000, ms % 60_000 / 1000)
+    }
+}
+
+/// Nearest-rank percentile of sorted values.
+fn percentile(sorted: &[u64], pct: usize) -> u64 {
+    if sorted.is_empty() {
+        return 0;
+    }
+    let rank = (pct * sorted.len()).div_ceil(100).max(1);
+    sorted[rank - 1]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn record(command: &str, outcome: &str, duration_ms: u64, args: Value) -> LoggedInvocation {
+        LoggedInvocation {
+            version: "0.0.0".to_string(),
+            ts: "2026-03-10T12:00:00.000Z".to_string(),
+            command: command.to_string(),
+            args,
+            outcome: outcome.to_string(),
+            error: None,
+            duration_ms,
+            result: None,
+        }
+    }
+
+    fn filter() -> HistoryFilterArgs {
+        HistoryFilterArgs::default()
+    }
+
+    #[test]
+    fn command_filter_matches_whole_words() {
+        let pause = record("pool pause", "ok", 1, json!({}));
+        let runs = record("runs", "ok", 1, json!({}));
+        let by = |command: &str| HistoryFilterArgs {
+            command: Some(command.to_string()),
+            ..filter()
+        };
+        assert!(matches(&pause, &by("pool"), None));
+        assert!(matches(&pause, &by("pool pause"), None));
+        assert!(!matches(&runs, &by("run"), None));
+    }
+
+    #[test]
+    fn errors_query_and_since_filters() {
+        let failed = record(
+            "run",
+            "error",
+            1,
+            json!({"query": "Happy-T", "sets": ["a=1"]}),
+        );
+        let ok = record("run", "ok", 1, json!({"query": "other"}));
+        let errors = HistoryFilterArgs {
+            errors: true,
+            ..filter()
+        };
+        assert!(matches(&failed, &errors, None));
+        assert!(!matches(&ok, &errors, None));
+
+        let query = HistoryFilterArgs {
+            query: Some("happy-t".to_string()),
+            ..filter()
+        };
+        assert!(matches(&failed, &query, None));
+        assert!(!matches(&ok, &query, None));
+
+        let later = timefmt::parse_timestamp("2026-03-10T13:00:00Z");
+        assert!(!matches(&ok, &filter(), later));
+    }
+
+    #[test]
+    fn summary_counts_and_percentiles() {
+        let mut records: Vec<LoggedInvocation> = (1..=20)
+            .map(|i| {
+                record(
+                    "run",
+                    if i == 20 { "error" } else { "ok" },
+                    i * 100,
+                    json!({}),
+                )
+            })
+            .collect();
+        records.push(record("ls", "ok", 40, json!({})));
+
+        assert_eq!(
+            summarize(&records),
+            vec![
+                CommandSummary {
+                    command: "ls".to_string(),
+                    count: 1,
+                    errors: 0,
+                    p50_ms: 40,
+                    p95_ms: 40,
+                },
+                CommandSummary {
+                    command: "run".to_string(),
+                    count: 20,
+                    errors: 1,
+                    p50_ms: 1000,
+                    p95_ms: 1900,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn args_summary_is_compact() {
+        let args = json!({
+            "query": "happy-t", "watch": true, "json": false, "sets": ["a=1", "b=2"],
+            "tags": [], "params_file": null, "limit": 10
+        });
+        assert_eq!(args_summary(&args), "happy-t limit=10 sets=a=1,b=2 watch");
+    }
+
+    #[test]
+    fn format_ms_units() {
+        assert_eq!(format_ms(850), "850ms");
+        assert_eq!(format_ms(2_449), "2.4s");
+        assert_eq!(format_ms(185_000), "3m 05s");
+    }
+
+    #[test]
+    fn rejects_bad_since() {
+        let args = HistoryFilterArgs {
+            since: Some("yesterday".to_string()),
+            ..filter()
+        };
+        assert!(matches!(
+            run(args, 10, false, OutputFormat::Table, TimeDisplay::default()),
+            Err(PfpError::Validation(_))
+        ));
+    }
+}
@@ -1,10 +1,16 @@
 use crate::client::PrefectClient;
 use crate::error::{PfpError, Result};
-use crate::models::FlowRun;
+use crate::models::FlowRunDetail;
 use crate::output;
 use crate::resolve;
+use crate::timefmt::TimeDisplay;
 
-pub async fn run(client: PrefectClient, flow_run_id: String, json: bool) -> Result<()> {
+pub async fn run(
+    client: PrefectClient,
+    flow_run_id: String,
+    json: bool,
+    time: TimeDisplay,
+) -> Result<()> {
     if !resolve::is_full_uuid(&flow_run_id) {
         return Err(PfpError::Validation(
             "inspect requires a full flow run UUID".to_string(),
@@ -12,13 +18,15 @@ pub async fn run(client: PrefectClient, flow_run_id: String, json: bool) -> Resu
     }
 
     let value = client.get_flow_run(&flow_run_id).await?;
-    let flow_run: FlowRun =
-        serde_json::from_value(value).map_err(|error| PfpError::Api(error.to_string()))?;
 
+    // JSON mode passes the API object through untouched; the typed model
+    // would drop every field it does not know about.
     if json {
-        output::print_json(&flow_run);
+        output::print_json(&value);
     } else {
-        output::print_flow_runs_table(&[flow_run]);
+        let detail: FlowRunDetail =
+            serde_json::from_value(value).map_err(|error| PfpError::Api(error.to_string()))?;
+        output::print_flow_run_detail(&detail, time);
     }
 
     Ok(())
@@ -33,6 +41,7 @@ mod tests {
         PrefectClient::new(Config {
             api_url: server.url(),
             auth_header: Some("Basic dGVzdDp0ZXN0".to_string()),
+            read_only: false,
         })
     }
 
@@ -52,9 +61,41 @@ mod tests {
             .create_async()
             .await;
 
-        run(test_client(&server), flow_run_id.to_string(), true)
-            .await
-            .unwrap();
+        run(
+            test_client(&server),
+            flow_run_id.to_string(),
+            true,
+            TimeDisplay::default(),
+        )
+        .await
+        .unwrap();
+
+        request.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn human_view_accepts_full_api_object() {
+        let mut server = mockito::Server::new_async().await;
+        let flow_run_id = "aaaaaaaa-bbbb-cccc-dddd-eeeeeeeeeeee";
+        let request = server
+            .mock("GET", format!("/flow_runs/{flow_run_id}").as_str())
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(format!(
+                r#"{{"id":"{flow_run_id}","name":"canary","state_type":"FAILED","state_name":"Failed","state":{{"type":"FAILED","message":"boom"}},"tags":["manual"],"run_count":1,"parameters":{{"config":{{"action":"plan"}}}}}}"#
+            ))
+            .expect(1)
+            .create_async()
+            .await;
+
+        run(
+            test_client(&server),
+            flow_run_id.to_string(),
+            false,
+            TimeDisplay::default(),
+        )
+        .await
+        .unwrap();
 
         request.assert_async().await;
     }
@@ -63,9 +104,14 @@ mod tests {
     async fn rejects_prefix_without_network_lookup() {
         let server = mockito::Server::new_async().await;
 
-        let error = run(test_client(&server), "aaaaaaaa".to_string(), true)
-            .await
-            .unwrap_err();
+        let error = run(
+            test_client(&server),
+            "aaaaaaaa".to_string(),
+            true,
+            TimeDisplay::default(),
+        )
+        .await
+        .unwrap_err();
 
         assert_eq!(error.to_string(), "inspect requires a full flow run UUID");
     }
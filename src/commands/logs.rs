@@ -1,52 +1,370 @@
-use crate::client::PrefectClient;
+use crate::client::{FlowRunFilter, LogFilter, PrefectClient};
 use crate::error::{PfpError, Result};
-use crate::models::{FlowRun, LogEntry};
-use crate::output;
+use crate::export::{LogFile, LogFileFormat};
+use crate::models::{self, FlowRun, LogEntry};
+use crate::output::{self, OutputFormat};
 use crate::resolve;
+use crate::timefmt::TimeDisplay;
+use crate::timespec;
+use regex::Regex;
+use std::collections::HashMap;
+use std::path::PathBuf;
 
 const DEFAULT_LIMIT: usize = 10_000;
 const FOLLOW_POLL_SECS: u64 = 3;
+/// How many of a deployment's most recent runs `--deployment` merges.
+const DEPLOYMENT_RUN_LIMIT: usize = 50;
 
+/// Log selection flags for `pfp logs`.
+#[derive(Debug, Default, clap::Args)]
+pub struct LogFilterArgs {
+    /// Merge the logs of this deployment's recent flow runs (substring match)
+    #[arg(long, conflicts_with = "flow_run_ids")]
+    pub deployment: Option<String>,
+    /// Only show logs of one task run (name, name substring, or ID)
+    #[arg(long)]
+    pub task: Option<String>,
+    /// Minimum log level (debug, info, warning, error, critical, or a number)
+    #[arg(long)]
+    pub level: Option<String>,
+    /// Only logs at or after this time (e.g. 30m, 2026-03-01, RFC 3339)
+    #[arg(long)]
+    pub since: Option<String>,
+    /// Only logs at or before this time
+    #[arg(long)]
+    pub until: Option<String>,
+    /// Only logs whose message matches this regular expression
+    #[arg(long)]
+    pub grep: Option<String>,
+    /// Show only the last N matching entries (a bare --tail is a deprecated
+    /// alias for --follow)
+    #[arg(long, value_name = "N", num_args = 0..=1)]
+    pub tail: Option<Option<usize>>,
+}
+
+impl LogFilterArgs {
+    /// Whether `--tail` was given without a count, its meaning before it
+    /// took one.
+    pub fn legacy_follow(&self) -> bool {
+        matches!(self.tail, Some(None))
+    }
+}
+
+/// The flow runs whose logs are shown. `names` is only filled when several
+/// runs are merged, and is used to prefix each line with its run's name.
+struct Targets {
+    ids: Vec<String>,
+    names: HashMap<String, String>,
+    scope: String,
+}
+
+/// Resolve the flow runs to show. With a deployment, its most recent runs
+/// expected to start at or before `until` are taken; bounding by time keeps
+/// future scheduled runs, which sort first, from crowding out the runs that
+/// actually logged something.
+async fn resolve_targets(
+    client: &PrefectClient,
+    flow_run_ids: &[String],
+    deployment: Option<&str>,
+    until: chrono::DateTime<chrono::Utc>,
+) -> Result<Targets> {
+    let runs: Vec<FlowRun> = match deployment {
+        Some(query) => {
+            let deployment = resolve::resolve_deployment(client, query).await?;
+            let filter = FlowRunFilter {
+                deployment_id: Some(deployment.id.clone()),
+                until: Some(until),
+                ..Default::default()
+            };
+            let runs: Vec<FlowRun> = client
+                .filter_flow_runs(&filter, DEPLOYMENT_RUN_LIMIT)
+                .await?
+                .into_iter()
+                .filter_map(|v| serde_json::from_value(v).ok())
+                .collect();
+            if runs.is_empty() {
+                return Err(PfpError::NoMatch(format!(
+                    "no flow runs found for deployment {}",
+                    deployment.full_name()
+                )));
+            }
+            runs
+        }
+        None if flow_run_ids.len() == 1 => {
+            let id = resolve::resolve_flow_run(client, &flow_run_ids[0]).await?;
+            return Ok(Targets {
+                scope: format!("flow run {}", id),
+                ids: vec![id],
+                names: HashMap::new(),
+            });
+        }
+        None => {
+            let mut runs = Vec::with_capacity(flow_run_ids.len());
+            for input in flow_run_ids {
+                let id = resolve::resolve_flow_run(client, input).await?;
+                if runs.iter().any(|r: &FlowRun| r.id == id) {
+                    continue;
+                }
+                runs.push(
+                    serde_json::from_value(client.get_flow_run(&id).await?)
+                        .map_err(|e| PfpError::Api(e.to_string()))?,
+                );
+            }
+            runs
+        }
+    };
+
+    Ok(Targets {
+        scope: format!("{} flow runs", runs.len()),
+        ids: runs.iter().map(|r| r.id.clone()).collect(),
+        names: runs.into_iter().map(|r| (r.id, r.name)).collect(),
+    })
+}
+
+/// Flags for writing logs to a file instead of the terminal.
+#[derive(Debug, Default, clap::Args)]
+pub struct LogExportArgs {
+    /// Write logs to this file instead of the terminal
+    #[arg(long, value_name = "PATH", conflicts_with_all = ["json", "jsonl"])]
+    pub output: Option<PathBuf>,
+    /// File format [default: from the file extension, else text]
+    #[arg(long = "format", value_enum, requires = "output")]
+    pub file_format: Option<LogFileFormat>,
+    /// Gzip-compress the file
+    #[arg(long, requires = "output")]
+    pub gzip: bool,
+}
+
+#[allow(clippy::too_many_arguments)]
 pub async fn run(
     client: PrefectClient,
-    flow_run_id: String,
+    flow_run_ids: Vec<String>,
+    args: LogFilterArgs,
+    export: LogExportArgs,
     limit: Option<usize>,
     follow: bool,
-    json: bool,
+    format: OutputFormat,
+    time: TimeDisplay,
 ) -> Result<()> {
-    let resolved_id = resolve::resolve_flow_run(&client, &flow_run_id).await?;
+    // Validate every flag before any network work.
+    let now = chrono::Utc::now();
+    let parse_bound = |input: &Option<String>| {
+        input
+            .as_deref()
+            .map(|s| timespec::parse_time_bound(s, now))
+            .transpose()
+            .map_err(PfpError::Validation)
+    };
+    let since = parse_bound(&args.since)?;
+    let until = parse_bound(&args.until)?;
+    let min_level = args
+        .level
+        .as_deref()
+        .map(|level| {
+            models::parse_log_level(level)
+                .ok_or_else(|| PfpError::Validation(format!("unknown log level '{}'", level)))
+        })
+        .transpose()?;
+    let grep = args
+        .grep
+        .as_deref()
+        .map(Regex::new)
+        .transpose()
+        .map_err(|e| PfpError::Validation(format!("invalid --grep pattern: {}", e)))?;
+    let follow = follow || args.legacy_follow();
+    if args.legacy_follow() {
+        eprintln!(
+            "Warning: --tail without a count is deprecated; use --follow (-f) to follow logs, \
+             or --tail N to show the last N entries."
+        );
+    }
+    if follow && format == OutputFormat::Json {
+        return Err(PfpError::Validation(
+            "--json prints one array per fetch and cannot be followed; use --jsonl for a \
+             stream of one JSON object per line"
+                .to_string(),
+        ));
+    }
+    let tail = args.tail.flatten();
+    if args.task.is_some() && (args.deployment.is_some() || flow_run_ids.len() != 1) {
+        return Err(PfpError::Validation(
+            "--task needs exactly one flow run".to_string(),
+        ));
+    }
+
+    let runs_until = until.map_or(now, |until| until.min(now));
+    let targets = resolve_targets(
+        &client,
+        &flow_run_ids,
+        args.deployment.as_deref(),
+        runs_until,
+    )
+    .await?;
+    let mut filter = LogFilter {
+        flow_run_ids: targets.ids.clone(),
+        min_level,
+        since,
+        until,
+        ..Default::default()
+    };
+    if let Some(task) = &args.task {
+        filter.task_run_id = Some(resolve::resolve_task_run(&client, &targets.ids[0], task).await?);
+    }
+
+    // Only create (and so truncate) the export file once every target has
+    // resolved.
+    let file = match &export.output {
+        Some(path) => {
+            let format = export
+                .file_format
+                .unwrap_or_else(|| LogFileFormat::from_path(path));
+            Some(LogFile::create(path, format, export.gzip, follow, time)?)
+        }
+        None => None,
+    };
+    let mut destination = Destination { file, format, time };
 
     // Initial fetch
     let effective_limit = limit.unwrap_or(DEFAULT_LIMIT);
-    let values = client
-        .get_flow_run_logs(&resolved_id, effective_limit, 0)
-        .await?;
-    let mut total_seen = values.len();
-    let logs: Vec<LogEntry> = values
-        .into_iter()
-        .filter_map(|v| serde_json::from_value(v).ok())
-        .collect();
+    let (values, total_seen) = match tail {
+        Some(count) => {
+            let tail = fetch_tail(&client, &filter, count, grep.as_ref()).await?;
+            // Offsets count from the start of the history, which a tail never
+            // reads. Follow from the newest entry's timestamp instead, skipping
+            // the entries at that instant that were already fetched. With
+            // nothing fetched, follow from the time of the request so the
+            // history is not replayed.
+            let resume = tail.newest.as_deref().and_then(|ts| {
+                chrono::DateTime::parse_from_rfc3339(ts)
+                    .ok()
+                    .map(|parsed| parsed.with_timezone(&chrono::Utc))
+            });
+            let seen = match resume {
+                Some(parsed) => {
+                    filter.since = Some(parsed);
+                    tail.at_newest
+                }
+                None => {
+                    filter.since = Some(since.map_or(now, |since| since.max(now)));
+                    0
+                }
+            };
+            (tail.values, seen)
+        }
+        None => {
+            let values = client
+                .get_flow_run_logs(&filter, effective_limit, 0)
+                .await?;
+            let fetched = values.len();
+            (values, fetched)
+        }
+    };
+    let fetched = values.len();
+    let logs = to_entries(values, grep.as_ref(), &targets.names);
 
-    if limit.is_none() && logs.len() >= DEFAULT_LIMIT && !follow {
+    if limit.is_none() && tail.is_none() && fetched >= DEFAULT_LIMIT && !follow {
         eprintln!(
             "Warning: output capped at {} entries. Use --limit to adjust.",
             DEFAULT_LIMIT
         );
     }
 
-    if json {
-        output::print_json(&logs);
-    } else if logs.is_empty() && !follow {
-        println!("No logs found for flow run {}", resolved_id);
+    if destination.file.is_none() && format == OutputFormat::Table && logs.is_empty() && !follow {
+        println!("No logs found for {}", targets.scope);
     } else {
-        output::print_logs(&logs);
+        destination.emit(&logs)?;
+    }
+
+    if follow {
+        follow_logs(
+            &client,
+            &targets,
+            &filter,
+            total_seen,
+            grep.as_ref(),
+            &mut destination,
+        )
+        .await?;
+    }
+
+    if let Some(file) = destination.file {
+        let path = file.path().display().to_string();
+        let written = file.finish()?;
+        eprintln!("Wrote {} log entries to {}", written, path);
     }
+    Ok(())
+}
 
-    if !follow {
-        return Ok(());
+/// The newest entries of a `--tail`, oldest first.
+struct Tail {
+    values: Vec<serde_json::Value>,
+    /// Timestamp of the newest entry fetched, matching or not
+    newest: Option<String>,
+    /// How many fetched entries share that timestamp
+    at_newest: usize,
+}
+
+/// Fetch the newest `count` entries whose message matches `grep`. Without a
+/// pattern that is one query; with one, pages back through the history
+/// until enough entries match or it runs out.
+async fn fetch_tail(
+    client: &PrefectClient,
+    filter: &LogFilter,
+    count: usize,
+    grep: Option<&Regex>,
+) -> Result<Tail> {
+    const PAGE_SIZE: usize = 200;
+    let mut tail = Tail {
+        values: Vec::new(),
+        newest: None,
+        at_newest: 0,
+    };
+    let mut offset = 0;
+    while tail.values.len() < count {
+        let batch = match grep {
+            Some(_) => PAGE_SIZE,
+            None => count - tail.values.len(),
+        };
+        let page = client
+            .get_flow_run_logs_newest_first(filter, batch, offset)
+            .await?;
+        let fetched = page.len();
+        for value in page {
+            let timestamp = value["timestamp"].as_str().map(str::to_string);
+            if tail.newest.is_none() {
+                tail.newest = timestamp.clone();
+            }
+            if timestamp.is_some() && timestamp == tail.newest {
+                tail.at_newest += 1;
+            }
+            let matches = grep.is_none_or(|re| {
+                value["message"]
+                    .as_str()
+                    .is_some_and(|message| re.is_match(message))
+            });
+            if matches && tail.values.len() < count {
+                tail.values.push(value);
+            }
+        }
+        if fetched < batch {
+            break;
+        }
+        offset += fetched;
     }
+    tail.values.reverse();
+    Ok(tail)
+}
 
-    // Follow mode: poll for new logs until flow run is terminal
+/// Poll for new logs until every followed flow run is terminal, then drain
+/// any stragglers.
+async fn follow_logs(
+    client: &PrefectClient,
+    targets: &Targets,
+    filter: &LogFilter,
+    mut total_seen: usize,
+    grep: Option<&Regex>,
+    destination: &mut Destination,
+) -> Result<()> {
     eprintln!("Following logs (Ctrl+C to stop)...");
 
     loop {
@@ -54,48 +372,30 @@ pub async fn run(
 
         // Fetch new logs from where we left off
         let new_values = client
-            .get_flow_run_logs(&resolved_id, DEFAULT_LIMIT, total_seen)
+            .get_flow_run_logs(filter, DEFAULT_LIMIT, total_seen)
             .await?;
         let fetched = new_values.len();
-        let new_logs: Vec<LogEntry> = new_values
-            .into_iter()
-            .filter_map(|v| serde_json::from_value(v).ok())
-            .collect();
+        let new_logs = to_entries(new_values, grep, &targets.names);
 
         if fetched > 0 {
             total_seen += fetched;
-            if json {
-                output::print_json(&new_logs);
-            } else {
-                output::print_logs(&new_logs);
-            }
+            destination.emit(&new_logs)?;
         }
 
-        // Check if flow run reached a terminal state
-        let flow_run_value = client.get_flow_run(&resolved_id).await?;
-        let flow_run: FlowRun =
-            serde_json::from_value(flow_run_value).map_err(|e| PfpError::Api(e.to_string()))?;
-
-        if flow_run.is_terminal() {
+        // Stop once every followed flow run has reached a terminal state
+        if all_terminal(client, &targets.ids).await? {
             // Drain any remaining logs after terminal state
             loop {
                 let final_values = client
-                    .get_flow_run_logs(&resolved_id, DEFAULT_LIMIT, total_seen)
+                    .get_flow_run_logs(filter, DEFAULT_LIMIT, total_seen)
                     .await?;
                 let fetched = final_values.len();
                 if fetched == 0 {
                     break;
                 }
                 total_seen += fetched;
-                let final_logs: Vec<LogEntry> = final_values
-                    .into_iter()
-                    .filter_map(|v| serde_json::from_value(v).ok())
-                    .collect();
-                if json {
-                    output::print_json(&final_logs);
-                } else {
-                    output::print_logs(&final_logs);
-                }
+                let final_logs = to_entries(final_values, grep, &targets.names);
+                destination.emit(&final_logs)?;
             }
 
             break;
@@ -105,6 +405,63 @@ pub async fn run(
     Ok(())
 }
 
+async fn all_terminal(client: &PrefectClient, flow_run_ids: &[String]) -> Result<bool> {
+    for id in flow_run_ids {
+        let flow_run: FlowRun = serde_json::from_value(client.get_flow_run(id).await?)
+            .map_err(|e| PfpError::Api(e.to_string()))?;
+        if !flow_run.is_terminal() {
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
+
+/// Where fetched batches go: the export file if there is one, else the
+/// terminal.
+struct Destination {
+    file: Option<LogFile>,
+    format: OutputFormat,
+    time: TimeDisplay,
+}
+
+impl Destination {
+    fn emit(&mut self, logs: &[LogEntry]) -> Result<()> {
+        match &mut self.file {
+            Some(file) => file.write_batch(logs),
+            None => {
+                match self.format {
+                    OutputFormat::Table => output::print_logs(logs, self.time),
+                    OutputFormat::Json => output::print_json(&logs),
+                    OutputFormat::Jsonl => output::print_json_lines(logs),
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Deserialize fetched log values, keeping only messages matching `grep` and
+/// tagging each entry with its run's name when several runs are merged.
+fn to_entries(
+    values: Vec<serde_json::Value>,
+    grep: Option<&Regex>,
+    run_names: &HashMap<String, String>,
+) -> Vec<LogEntry> {
+    values
+        .into_iter()
+        .filter_map(|v| serde_json::from_value::<LogEntry>(v).ok())
+        .filter(|entry| grep.is_none_or(|re| re.is_match(&entry.message)))
+        .map(|mut entry| {
+            entry.flow_run_name = entry
+                .flow_run_id
+                .as_ref()
+                .and_then(|id| run_names.get(id))
+                .cloned();
+            entry
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -114,6 +471,7 @@ mod tests {
         let config = Config {
             api_url: server.url(),
             auth_header: Some("Basic dGVzdDp0ZXN0".to_string()),
+            read_only: false,
         };
         PrefectClient::new(config)
     }
@@ -199,7 +557,17 @@ mod tests {
         let client = test_client(&server);
 
         // Run with follow=true — should complete without hanging
-        let result = run(client, flow_run_id.to_string(), None, true, false).await;
+        let result = run(
+            client,
+            vec![flow_run_id.to_string()],
+            LogFilterArgs::default(),
+            LogExportArgs::default(),
+            None,
+            true,
+            OutputFormat::Table,
+            TimeDisplay::default(),
+        )
+        .await;
 
         assert!(result.is_ok());
         logs_mock_1.assert_async().await;
@@ -259,7 +627,17 @@ mod tests {
 
         let client = test_client(&server);
 
-        let result = run(client, flow_run_id.to_string(), None, true, false).await;
+        let result = run(
+            client,
+            vec![flow_run_id.to_string()],
+            LogFilterArgs::default(),
+            LogExportArgs::default(),
+            None,
+            true,
+            OutputFormat::Table,
+            TimeDisplay::default(),
+        )
+        .await;
 
         assert!(result.is_ok());
         logs_mock_initial.assert_async().await;
@@ -341,7 +719,17 @@ mod tests {
 
         let client = test_client(&server);
 
-        let result = run(client, flow_run_id.to_string(), None, true, false).await;
+        let result = run(
+            client,
+            vec![flow_run_id.to_string()],
+            LogFilterArgs::default(),
+            LogExportArgs::default(),
+            None,
+            true,
+            OutputFormat::Table,
+            TimeDisplay::default(),
+        )
+        .await;
 
         assert!(result.is_ok());
         logs_mock_initial.assert_async().await;
@@ -350,4 +738,476 @@ mod tests {
         logs_mock_empty.assert_async().await;
         state_mock.assert_async().await;
     }
+
+    #[tokio::test]
+    async fn task_filter_resolves_name_and_filters_logs() {
+        let mut server = mockito::Server::new_async().await;
+        let flow_run_id = "aaaaaaaa-bbbb-cccc-dddd-eeeeeeeeeeee";
+
+        let tasks_mock = server
+            .mock("POST", "/task_runs/filter")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"[{"id":"task-apply-0","name":"terraform_apply-0","state_type":"FAILED"}]"#,
+            )
+            .expect(1)
+            .create_async()
+            .await;
+        let logs_mock = server
+            .mock("POST", "/logs/filter")
+            .match_body(mockito::Matcher::PartialJsonString(
+                r#"{"logs":{"task_run_id":{"any_":["task-apply-0"]}}}"#.to_string(),
+            ))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"[{"level":40,"message":"apply failed","timestamp":"2026-01-01T00:00:00Z"}]"#,
+            )
+            .expect(1)
+            .create_async()
+            .await;
+
+        let args = LogFilterArgs {
+            task: Some("apply".to_string()),
+            ..Default::default()
+        };
+        let result = run(
+            test_client(&server),
+            vec![flow_run_id.to_string()],
+            args,
+            LogExportArgs::default(),
+            None,
+            false,
+            OutputFormat::Table,
+            TimeDisplay::default(),
+        )
+        .await;
+
+        assert!(result.is_ok(), "{result:?}");
+        tasks_mock.assert_async().await;
+        logs_mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn invalid_level_and_grep_are_rejected_before_network() {
+        let server = mockito::Server::new_async().await;
+        let flow_run_id = "aaaaaaaa-bbbb-cccc-dddd-eeeeeeeeeeee";
+
+        for args in [
+            LogFilterArgs {
+                level: Some("loud".to_string()),
+                ..Default::default()
+            },
+            LogFilterArgs {
+                grep: Some("(unclosed".to_string()),
+                ..Default::default()
+            },
+        ] {
+            let err = run(
+                test_client(&server),
+                vec![flow_run_id.to_string()],
+                args,
+                LogExportArgs::default(),
+                None,
+                false,
+                OutputFormat::Table,
+                TimeDisplay::default(),
+            )
+            .await
+            .unwrap_err();
+            assert!(matches!(err, PfpError::Validation(_)));
+        }
+    }
+
+    #[tokio::test]
+    async fn deployment_merges_logs_of_its_recent_runs() {
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("POST", "/deployments/filter")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"[{"id":"dep-1","name":"prod","flow_id":"flow-1"}]"#)
+            .create_async()
+            .await;
+        server
+            .mock("POST", "/flows/filter")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"[{"id":"flow-1","name":"happy_terraform"}]"#)
+            .create_async()
+            .await;
+        let runs_mock = server
+            .mock("POST", "/flow_runs/filter")
+            .match_body(mockito::Matcher::AllOf(vec![
+                mockito::Matcher::PartialJsonString(
+                    r#"{"flow_runs":{"deployment_id":{"any_":["dep-1"]}}}"#.to_string(),
+                ),
+                // Future scheduled runs must not use up the run budget.
+                mockito::Matcher::Regex(r#""expected_start_time":\{"before_":"#.to_string()),
+            ]))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"[{"id":"run-a","name":"apply-a","state_type":"COMPLETED"},
+                    {"id":"run-b","name":"apply-b","state_type":"FAILED"}]"#,
+            )
+            .expect(1)
+            .create_async()
+            .await;
+        let logs_mock = server
+            .mock("POST", "/logs/filter")
+            .match_body(mockito::Matcher::PartialJsonString(
+                r#"{"logs":{"flow_run_id":{"any_":["run-a","run-b"]}}}"#.to_string(),
+            ))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"[{"level":20,"message":"hi","timestamp":"2026-01-01T00:00:00Z","flow_run_id":"run-b"}]"#,
+            )
+            .expect(1)
+            .create_async()
+            .await;
+
+        let args = LogFilterArgs {
+            deployment: Some("prod".to_string()),
+            ..Default::default()
+        };
+        let result = run(
+            test_client(&server),
+            vec![],
+            args,
+            LogExportArgs::default(),
+            None,
+            false,
+            OutputFormat::Table,
+            TimeDisplay::default(),
+        )
+        .await;
+
+        assert!(result.is_ok(), "{result:?}");
+        runs_mock.assert_async().await;
+        logs_mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn output_writes_logs_to_file_instead_of_terminal() {
+        let mut server = mockito::Server::new_async().await;
+        let flow_run_id = "aaaaaaaa-bbbb-cccc-dddd-eeeeeeeeeeee";
+        server
+            .mock("POST", "/logs/filter")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"[{"level":30,"message":"disk, almost full","timestamp":"2026-01-01T00:00:00Z"}]"#,
+            )
+            .create_async()
+            .await;
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("run.csv");
+
+        let export = LogExportArgs {
+            output: Some(path.clone()),
+            ..Default::default()
+        };
+        run(
+            test_client(&server),
+            vec![flow_run_id.to_string()],
+            LogFilterArgs::default(),
+            export,
+            None,
+            false,
+            OutputFormat::Table,
+            TimeDisplay::default(),
+        )
+        .await
+        .unwrap();
+
+        let csv = std::fs::read_to_string(&path).unwrap();
+        assert!(csv.starts_with("timestamp,level,"));
+        assert!(csv.contains(r#"2026-01-01T00:00:00Z,WARNING,,,,,"disk, almost full""#));
+    }
+
+    #[tokio::test]
+    async fn failed_resolution_leaves_streaming_export_file_alone() {
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("POST", "/deployments/filter")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body("[]")
+            .create_async()
+            .await;
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("run.log");
+        std::fs::write(&path, "earlier export\n").unwrap();
+
+        let args = LogFilterArgs {
+            deployment: Some("no-such-deployment".to_string()),
+            ..Default::default()
+        };
+        let export = LogExportArgs {
+            output: Some(path.clone()),
+            ..Default::default()
+        };
+        let result = run(
+            test_client(&server),
+            vec![],
+            args,
+            export,
+            None,
+            true,
+            OutputFormat::Table,
+            TimeDisplay::default(),
+        )
+        .await;
+
+        assert!(result.is_err());
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "earlier export\n");
+    }
+
+    #[tokio::test]
+    async fn task_with_several_runs_is_rejected_before_network() {
+        let server = mockito::Server::new_async().await;
+        let args = LogFilterArgs {
+            task: Some("apply".to_string()),
+            ..Default::default()
+        };
+
+        let err = run(
+            test_client(&server),
+            vec!["run-a".to_string(), "run-b".to_string()],
+            args,
+            LogExportArgs::default(),
+            None,
+            false,
+            OutputFormat::Table,
+            TimeDisplay::default(),
+        )
+        .await
+        .unwrap_err();
+
+        assert!(matches!(err, PfpError::Validation(ref msg) if msg.contains("--task")));
+    }
+
+    #[test]
+    fn merged_entries_carry_their_run_name() {
+        let values = vec![
+            serde_json::json!({"level":20,"message":"a","timestamp":"t","flow_run_id":"run-a"}),
+            serde_json::json!({"level":20,"message":"b","timestamp":"t","flow_run_id":"run-x"}),
+        ];
+        let names = HashMap::from([("run-a".to_string(), "apply-a".to_string())]);
+        let entries = to_entries(values, None, &names);
+        assert_eq!(entries[0].flow_run_name.as_deref(), Some("apply-a"));
+        assert_eq!(entries[1].flow_run_name, None);
+    }
+
+    #[test]
+    fn grep_keeps_only_matching_messages() {
+        let values = vec![
+            serde_json::json!({"level":20,"message":"Plan: 2 to add","timestamp":"t"}),
+            serde_json::json!({"level":20,"message":"Apply complete","timestamp":"t"}),
+        ];
+        let re = Regex::new("^Plan").unwrap();
+        let entries = to_entries(values, Some(&re), &HashMap::new());
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].message, "Plan: 2 to add");
+    }
+
+    #[tokio::test]
+    async fn tail_then_follow_resumes_from_newest_timestamp() {
+        let mut server = mockito::Server::new_async().await;
+        let flow_run_id = "aaaaaaaa-bbbb-cccc-dddd-eeeeeeeeeeee";
+
+        let tail_mock = server
+            .mock("POST", "/logs/filter")
+            .match_body(mockito::Matcher::PartialJsonString(
+                r#"{"sort":"TIMESTAMP_DESC","limit":2}"#.to_string(),
+            ))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"[
+                {"level":20,"message":"Newest","timestamp":"2026-01-01T00:00:05Z"},
+                {"level":20,"message":"Older","timestamp":"2026-01-01T00:00:04Z"}
+            ]"#,
+            )
+            .expect(1)
+            .create_async()
+            .await;
+
+        // Follow restarts at the newest timestamp, skipping the one entry
+        // already printed at that instant.
+        let follow_mock = server
+            .mock("POST", "/logs/filter")
+            .match_body(mockito::Matcher::PartialJsonString(
+                r#"{"logs":{"timestamp":{"after_":"2026-01-01T00:00:05+00:00"}},"sort":"TIMESTAMP_ASC","offset":1}"#
+                    .to_string(),
+            ))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body("[]")
+            .expect(2)
+            .create_async()
+            .await;
+
+        let state_mock = server
+            .mock("GET", format!("/flow_runs/{}", flow_run_id).as_str())
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(format!(
+                r#"{{"id":"{}","name":"test-run","state_type":"COMPLETED","state_name":"Completed"}}"#,
+                flow_run_id
+            ))
+            .expect(1)
+            .create_async()
+            .await;
+
+        let args = LogFilterArgs {
+            tail: Some(Some(2)),
+            ..Default::default()
+        };
+        let result = run(
+            test_client(&server),
+            vec![flow_run_id.to_string()],
+            args,
+            LogExportArgs::default(),
+            None,
+            true,
+            OutputFormat::Table,
+            TimeDisplay::default(),
+        )
+        .await;
+
+        assert!(result.is_ok(), "{result:?}");
+        tail_mock.assert_async().await;
+        follow_mock.assert_async().await;
+        state_mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn tail_with_grep_pages_back_until_enough_entries_match() {
+        let mut server = mockito::Server::new_async().await;
+        let newest: Vec<serde_json::Value> = (0..200)
+            .map(|i| {
+                serde_json::json!({
+                    "level": 20,
+                    "message": if i == 3 { "Plan: newest" } else { "noise" },
+                    "timestamp": format!("2026-01-01T01:{:02}:{:02}Z", 59 - i / 60, 59 - i % 60)
+                })
+            })
+            .collect();
+        let first_page = server
+            .mock("POST", "/logs/filter")
+            .match_body(mockito::Matcher::PartialJsonString(
+                r#"{"sort":"TIMESTAMP_DESC","limit":200,"offset":0}"#.to_string(),
+            ))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(serde_json::Value::from(newest).to_string())
+            .expect(1)
+            .create_async()
+            .await;
+        let second_page = server
+            .mock("POST", "/logs/filter")
+            .match_body(mockito::Matcher::PartialJsonString(
+                r#"{"sort":"TIMESTAMP_DESC","limit":200,"offset":200}"#.to_string(),
+            ))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"[
+                {"level":20,"message":"Plan: older","timestamp":"2026-01-01T00:00:02Z"},
+                {"level":20,"message":"Plan: oldest","timestamp":"2026-01-01T00:00:01Z"}
+            ]"#,
+            )
+            .expect(1)
+            .create_async()
+            .await;
+
+        let re = Regex::new("^Plan").unwrap();
+        let filter = LogFilter::for_flow_run("run-1");
+        let tail = fetch_tail(&test_client(&server), &filter, 2, Some(&re))
+            .await
+            .unwrap();
+
+        let messages: Vec<&str> = tail
+            .values
+            .iter()
+            .map(|v| v["message"].as_str().unwrap())
+            .collect();
+        assert_eq!(messages, vec!["Plan: older", "Plan: newest"]);
+        assert_eq!(tail.newest.as_deref(), Some("2026-01-01T01:59:59Z"));
+        assert_eq!(tail.at_newest, 1);
+        first_page.assert_async().await;
+        second_page.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn empty_tail_follows_from_the_request_time() {
+        let mut server = mockito::Server::new_async().await;
+        let flow_run_id = "aaaaaaaa-bbbb-cccc-dddd-eeeeeeeeeeee";
+
+        let tail_mock = server
+            .mock("POST", "/logs/filter")
+            .match_body(mockito::Matcher::PartialJsonString(
+                r#"{"sort":"TIMESTAMP_DESC","limit":5}"#.to_string(),
+            ))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body("[]")
+            .expect(1)
+            .create_async()
+            .await;
+        // With no newest entry to resume from, the follow is still bounded in
+        // time instead of replaying the history from offset 0. Any other
+        // request is unmatched and fails the command.
+        let follow_mock = server
+            .mock("POST", "/logs/filter")
+            .match_body(mockito::Matcher::AllOf(vec![
+                mockito::Matcher::PartialJsonString(
+                    r#"{"sort":"TIMESTAMP_ASC","offset":0}"#.to_string(),
+                ),
+                mockito::Matcher::Regex(r#""after_":"20"#.to_string()),
+            ]))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body("[]")
+            .expect(2)
+            .create_async()
+            .await;
+        let state_mock = server
+            .mock("GET", format!("/flow_runs/{}", flow_run_id).as_str())
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(format!(
+                r#"{{"id":"{}","name":"test-run","state_type":"COMPLETED","state_name":"Completed"}}"#,
+                flow_run_id
+            ))
+            .expect(1)
+            .create_async()
+            .await;
+
+        let args = LogFilterArgs {
+            level: Some("error".to_string()),
+            tail: Some(Some(5)),
+            ..Default::default()
+        };
+        let result = run(
+            test_client(&server),
+            vec![flow_run_id.to_string()],
+            args,
+            LogExportArgs::default(),
+            None,
+            true,
+            OutputFormat::Table,
+            TimeDisplay::default(),
+        )
+        .await;
+
+        assert!(result.is_ok(), "{result:?}");
+        tail_mock.assert_async().await;
+        follow_mock.assert_async().await;
+        state_mock.assert_async().await;
+    }
 }
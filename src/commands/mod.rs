@@ -1,10 +1,14 @@
 pub mod cancel;
+pub mod history;
 pub mod inspect;
 pub mod logs;
 pub mod ls;
 pub mod pause;
+pub mod plan_run;
 pub mod pool;
 pub mod resume;
 pub mod run;
 pub mod runs;
 pub mod schedule_resume;
+pub mod tasks;
+pub mod wait;
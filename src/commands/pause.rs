@@ -1,9 +1,14 @@
 use crate::client::PrefectClient;
 use crate::error::Result;
+use crate::guard::Guard;
 use crate::resolve;
 
-pub async fn run(client: PrefectClient, query: String) -> Result<()> {
+pub async fn run(client: PrefectClient, query: String, guard: &Guard) -> Result<()> {
     let deployment = resolve::resolve_deployment(&client, &query).await?;
+    guard
+        .policy()
+        .check_pause_deployment("Pause deployment", &deployment.full_name())?;
+    guard.confirm_deployment("Pause deployment", &deployment.full_name())?;
     client.set_deployment_paused(&deployment.id, true).await?;
     eprintln!("Paused: {}", deployment.full_name());
     Ok(())
@@ -0,0 +1,591 @@
+use crate::client::PrefectClient;
+use crate::error::{PfpError, Result};
+use crate::guard::Guard;
+use crate::logger;
+use crate::models::{self, Deployment, FlowRun};
+use crate::output::{self, OutputFormat};
+use crate::resolve;
+use crate::settings::Notifier;
+use crate::timefmt::TimeDisplay;
+use colored::Colorize;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// A pipeline file: steps run one at a time, in file order.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Pipeline {
+    #[serde(rename = "step", default)]
+    pub steps: Vec<Step>,
+}
+
+/// One deployment run in a pipeline. Dependencies name earlier steps: the
+/// step runs only if every `on_success` step completed, and only if every
+/// `on_failure` step ran and did not. `after` only records an ordering:
+/// steps already run one at a time in file order, so it never skips a step.
+/// A step without conditions always runs.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Step {
+    pub name: String,
+    /// Deployment name (substring match)
+    pub deployment: String,
+    /// Parameter overrides, merged over the deployment defaults
+    #[serde(default)]
+    pub params: BTreeMap<String, Value>,
+    /// Additional flow-run tags ("manual" is always included)
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub after: Vec<String>,
+    #[serde(default)]
+    pub on_success: Vec<String>,
+    #[serde(default)]
+    pub on_failure: Vec<String>,
+}
+
+const STEP_SKIPPED: &str = "Skipped";
+const STEP_ERROR: &str = "Error";
+
+/// How one step of a pipeline ended.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct StepReport {
+    pub step: String,
+    pub deployment: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub flow_run_id: Option<String>,
+    /// The run's final state name, `Skipped`, or `Error` when pfp could not
+    /// create or watch the run
+    pub state: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+impl StepReport {
+    fn completed(&self) -> bool {
+        self.state == "Completed"
+    }
+
+    fn skipped(&self) -> bool {
+        self.state == STEP_SKIPPED
+    }
+}
+
+impl Pipeline {
+    pub fn load(path: &str) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| PfpError::Config(format!("Cannot read pipeline {}: {}", path, e)))?;
+        let pipeline: Self = toml::from_str(&content)
+            .map_err(|e| PfpError::Config(format!("Cannot parse pipeline {}: {}", path, e)))?;
+        pipeline.check().map_err(PfpError::Config)?;
+        Ok(pipeline)
+    }
+
+    /// Step names must be unique, and dependencies must name an earlier
+    /// step, which also rules out cycles.
+    fn check(&self) -> std::result::Result<(), String> {
+        if self.steps.is_empty() {
+            return Err("Pipeline has no [[step]] entries".to_string());
+        }
+        for (index, step) in self.steps.iter().enumerate() {
+            let earlier = &self.steps[..index];
+            if earlier.iter().any(|s| s.name == step.name) {
+                return Err(format!("Step '{}' is defined twice", step.name));
+            }
+            if let Some(dependency) = step
+                .dependencies()
+                .find(|d| !earlier.iter().any(|s| &s.name == *d))
+            {
+                return Err(format!(
+                    "Step '{}' depends on '{}', which is not an earlier step",
+                    step.name, dependency
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Step {
+    fn dependencies(&self) -> impl Iterator<Item = &String> {
+        self.after
+            .iter()
+            .chain(&self.on_success)
+            .chain(&self.on_failure)
+    }
+
+    fn overrides(&self) -> Value {
+        Value::Object(self.params.clone().into_iter().collect())
+    }
+
+    /// Why the step must be skipped given the reports of earlier steps, if
+    /// it must.
+    fn skip_reason(&self, reports: &[StepReport]) -> Option<String> {
+        let report = |name: &String| reports.iter().find(|r| &r.step == name);
+        if let Some(name) = self
+            .on_success
+            .iter()
+            .find(|name| !report(name).is_some_and(StepReport::completed))
+        {
+            return Some(format!("{} did not complete", name));
+        }
+        if let Some(name) = self
+            .on_failure
+            .iter()
+            .find(|name| report(name).is_none_or(|r| r.completed() || r.skipped()))
+        {
+            return Some(format!("{} did not fail", name));
+        }
+        None
+    }
+}
+
+/// Run every step of a pipeline, watching each run, and print a per-step
+/// report; `json` prints only the report, as JSON. Each step's
+/// deployment is resolved, its parameters validated and the policy checked
+/// before the first run is created. Fails with the first error that
+/// stopped a step's run, else if any step's run did not complete.
+pub async fn run(
+    client: PrefectClient,
+    pipeline: Pipeline,
+    json: bool,
+    time: TimeDisplay,
+    guard: &Guard,
+    notifiers: &[Notifier],
+) -> Result<()> {
+    let mut planned: Vec<(&Step, Deployment, Value)> = Vec::new();
+    // Step parameters are not part of the logged arguments, so the
+    // schemas' secret fields have nothing to mask.
+    let mut secret_paths = BTreeSet::new();
+    for step in &pipeline.steps {
+        let deployment = resolve::resolve_deployment(&client, &step.deployment)
+            .await
+            .map_err(|e| in_step(step, e))?;
+        let parameters = super::run::checked_parameters(
+            &deployment,
+            &step.overrides(),
+            guard,
+            &mut secret_paths,
+        )
+        .map_err(|e| in_step(step, e))?;
+        planned.push((step, deployment, parameters));
+    }
+
+    let mut protected: Vec<String> = planned
+        .iter()
+        .map(|(_, deployment, _)| deployment.full_name())
+        .filter(|name| guard.is_protected_deployment(name))
+        .collect();
+    protected.sort();
+    protected.dedup();
+    guard.confirm("Run pipeline", &protected)?;
+
+    let mut reports: Vec<StepReport> = Vec::new();
+    let mut step_error: Option<PfpError> = None;
+    let mut stopped = false;
+    for (step, deployment, parameters) in planned {
+        let mut report = StepReport {
+            step: step.name.clone(),
+            deployment: deployment.full_name(),
+            flow_run_id: None,
+            state: STEP_SKIPPED.to_string(),
+            message: None,
+        };
+        if stopped {
+            report.message = Some("pipeline stopped".to_string());
+            reports.push(report);
+            continue;
+        }
+        if let Some(reason) = step.skip_reason(&reports) {
+            eprintln!("Step {}: skipped ({})", step.name, reason);
+            report.message = Some(reason);
+            reports.push(report);
+            continue;
+        }
+
+        // An error creating or watching the run becomes the step's report,
+        // so later steps still see it as a failure and the report is printed.
+        let ran: Result<()> = async {
+            let run_value = client
+                .create_flow_run(&deployment.id, parameters, step.tags.clone())
+                .await?;
+            let flow_run: FlowRun =
+                serde_json::from_value(run_value).map_err(|e| PfpError::Api(e.to_string()))?;
+            eprintln!(
+                "Step {}: created flow run '{}' ({}) of {}",
+                step.name,
+                flow_run.name,
+                flow_run.short_id(),
+                deployment.full_name()
+            );
+            report.flow_run_id = Some(flow_run.id.clone());
+            let progress = (!json).then_some(OutputFormat::Table);
+            let done =
+                super::run::watch_until_terminal(&client, &flow_run.id, progress, time, notifiers)
+                    .await?;
+            report.state = done.state_name.clone();
+            report.message = done.state_message().map(str::to_string);
+            Ok(())
+        }
+        .await;
+        if let Err(e) = ran {
+            eprintln!("Step {}: {}", step.name, e);
+            report.state = STEP_ERROR.to_string();
+            report.message = Some(e.to_string());
+            // Read-only mode and the policy would refuse every later step
+            // the same way, so they end the pipeline.
+            stopped = matches!(e, PfpError::ReadOnly(_) | PfpError::PolicyDenied(_));
+            step_error.get_or_insert(in_step(step, e));
+        }
+        reports.push(report);
+    }
+
+    let steps: serde_json::Map<String, Value> = reports
+        .iter()
+        .map(|r| (r.step.clone(), Value::from(r.state.as_str())))
+        .collect();
+    logger::record_result("steps", steps);
+
+    if json {
+        output::print_json(&reports);
+    } else {
+        print_report(&reports);
+    }
+
+    let failed: Vec<String> = reports
+        .iter()
+        .filter(|r| !r.skipped() && !r.completed())
+        .map(|r| format!("{} ({})", r.step, r.state))
+        .collect();
+    let summary = format!("pipeline steps did not complete: {}", failed.join(", "));
+    if let Some(e) = step_error {
+        return Err(annotate(e, |msg| format!("{}; {}", msg, summary)));
+    }
+    if failed.is_empty() {
+        Ok(())
+    } else {
+        Err(PfpError::FlowRunFailed(summary))
+    }
+}
+
+/// Name the step in errors raised while planning or running it.
+fn in_step(step: &Step, err: PfpError) -> PfpError {
+    annotate(err, |msg| format!("step {}: {}", step.name, msg))
+}
+
+/// Rewrite an error's message, keeping its exit code.
+fn annotate(err: PfpError, rewrite: impl FnOnce(String) -> String) -> PfpError {
+    match err {
+        PfpError::Validation(msg) => PfpError::Validation(rewrite(msg)),
+        PfpError::NoMatch(msg) => PfpError::NoMatch(rewrite(msg)),
+        PfpError::Api(msg) => PfpError::Api(rewrite(msg)),
+        PfpError::Http(e) => PfpError::Api(rewrite(e.to_string())),
+        PfpError::Timeout(msg) => PfpError::Timeout(rewrite(msg)),
+        PfpError::NotAccepted(msg) => PfpError::NotAccepted(rewrite(msg)),
+        PfpError::ReadOnly(msg) => PfpError::ReadOnly(rewrite(msg)),
+        PfpError::PolicyDenied(msg) => PfpError::PolicyDenied(rewrite(msg)),
+        other => other,
+    }
+}
+
+fn print_report(reports: &[StepReport]) {
+    println!();
+    println!(
+        "{:<16} {:<40} {:<12} {:<9} MESSAGE",
+        "STEP", "DEPLOYMENT", "STATE", "ID"
+    );
+    for r in reports {
+        let state = if r.skipped() {
+            r.state.dimmed()
+        } else {
+            output::state_color(&r.state)
+        };
+        println!(
+            "{:<16} {:<40} {:<12} {:<9} {}",
+            output::truncate(&r.step, 16),
+            output::truncate(&r.deployment, 40),
+            state,
+            r.flow_run_id.as_deref().map_or("-", models::short_id),
+            output::truncate(r.message.as_deref().unwrap_or(""), 60),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use serde_json::json;
+
+    const PIPELINE: &str = r#"
+[[step]]
+name = "plan"
+deployment = "terraform"
+params = { config = { action = "plan" } }
+
+[[step]]
+name = "apply"
+deployment = "terraform"
+params = { config = { action = "apply" } }
+tags = ["pipeline"]
+on_success = ["plan"]
+
+[[step]]
+name = "rollback"
+deployment = "terraform"
+params = { config = { action = "destroy" } }
+on_failure = ["apply"]
+
+[[step]]
+name = "ansible"
+deployment = "terraform"
+params = { config = { action = "configure" } }
+on_success = ["apply"]
+"#;
+
+    fn pipeline(toml: &str) -> std::result::Result<Pipeline, String> {
+        let pipeline: Pipeline = toml::from_str(toml).map_err(|e| e.to_string())?;
+        pipeline.check()?;
+        Ok(pipeline)
+    }
+
+    fn report(step: &str, state: &str) -> StepReport {
+        StepReport {
+            step: step.to_string(),
+            deployment: "f/d".to_string(),
+            flow_run_id: (state != "Skipped").then(|| format!("run-{step}")),
+            state: state.to_string(),
+            message: None,
+        }
+    }
+
+    #[test]
+    fn dependencies_must_name_earlier_steps() {
+        assert!(pipeline(PIPELINE).is_ok());
+        let forward = "[[step]]\nname = \"a\"\ndeployment = \"x\"\nafter = [\"b\"]\n\n[[step]]\nname = \"b\"\ndeployment = \"x\"\n";
+        assert!(pipeline(forward)
+            .unwrap_err()
+            .contains("not an earlier step"));
+        let twice = "[[step]]\nname = \"a\"\ndeployment = \"x\"\n\n[[step]]\nname = \"a\"\ndeployment = \"y\"\n";
+        assert!(pipeline(twice).unwrap_err().contains("defined twice"));
+        assert!(pipeline("").unwrap_err().contains("no [[step]]"));
+        assert!(
+            pipeline("[[step]]\nname = \"a\"\ndeployment = \"x\"\nwhen = \"always\"\n").is_err()
+        );
+    }
+
+    #[test]
+    fn skip_reasons_follow_earlier_outcomes() {
+        let steps = pipeline(PIPELINE).unwrap().steps;
+        let (apply, rollback) = (&steps[1], &steps[2]);
+
+        assert_eq!(apply.skip_reason(&[report("plan", "Completed")]), None);
+        assert_eq!(
+            apply.skip_reason(&[report("plan", "Failed")]),
+            Some("plan did not complete".to_string())
+        );
+        assert_eq!(rollback.skip_reason(&[report("apply", "Crashed")]), None);
+        assert!(rollback
+            .skip_reason(&[report("apply", "Completed")])
+            .is_some());
+        assert!(rollback
+            .skip_reason(&[report("apply", "Skipped")])
+            .is_some());
+    }
+
+    async fn mock_resolution(server: &mut mockito::Server) {
+        server
+            .mock("POST", "/deployments/filter")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                json!([{"id": "dep-1", "name": "terraform-prod", "flow_id": "flow-1",
+                        "parameters": {"config": {"action": "plan"}}}])
+                .to_string(),
+            )
+            .create_async()
+            .await;
+        server
+            .mock("POST", "/flows/filter")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"[{"id":"flow-1","name":"tf"}]"#)
+            .create_async()
+            .await;
+    }
+
+    fn test_client(server: &mockito::Server) -> PrefectClient {
+        PrefectClient::new(Config {
+            api_url: server.url(),
+            auth_header: None,
+            read_only: false,
+        })
+    }
+
+    #[tokio::test]
+    async fn runs_steps_and_reports_failure() {
+        let mut server = mockito::Server::new_async().await;
+        mock_resolution(&mut server).await;
+        let mut creates = Vec::new();
+        for (action, calls, state_type, state_name) in [
+            ("plan", 1, "COMPLETED", "Completed"),
+            ("apply", 1, "FAILED", "Failed"),
+            ("destroy", 1, "COMPLETED", "Completed"),
+            ("configure", 0, "", ""),
+        ] {
+            let created = server
+                .mock("POST", "/deployments/dep-1/create_flow_run")
+                .match_body(mockito::Matcher::PartialJsonString(format!(
+                    r#"{{"parameters":{{"config":{{"action":"{action}"}}}}}}"#
+                )))
+                .with_status(200)
+                .with_header("content-type", "application/json")
+                .with_body(format!(
+                    r#"{{"id":"run-{action}","name":"{action}-run","state_type":"SCHEDULED","state_name":"Scheduled"}}"#
+                ))
+                .expect(calls)
+                .create_async()
+                .await;
+            creates.push(created);
+            server
+                .mock("GET", format!("/flow_runs/run-{action}").as_str())
+                .with_status(200)
+                .with_header("content-type", "application/json")
+                .with_body(format!(
+                    r#"{{"id":"run-{action}","name":"{action}-run","state_type":"{state_type}","state_name":"{state_name}"}}"#
+                ))
+                .create_async()
+                .await;
+        }
+        let err = run(
+            test_client(&server),
+            pipeline(PIPELINE).unwrap(),
+            true,
+            TimeDisplay::default(),
+            &Guard::default(),
+            &[],
+        )
+        .await
+        .unwrap_err();
+
+        assert!(
+            matches!(err, PfpError::FlowRunFailed(ref msg)
+                if msg == "pipeline steps did not complete: apply (Failed)"),
+            "{err:?}"
+        );
+        assert_eq!(err.exit_code(), 1);
+        for created in creates {
+            created.assert_async().await;
+        }
+    }
+
+    #[tokio::test]
+    async fn step_error_is_reported_and_failure_steps_still_run() {
+        let mut server = mockito::Server::new_async().await;
+        mock_resolution(&mut server).await;
+        let refused = server
+            .mock("POST", "/deployments/dep-1/create_flow_run")
+            .match_body(mockito::Matcher::PartialJsonString(
+                r#"{"parameters":{"config":{"action":"apply"}}}"#.to_string(),
+            ))
+            .with_status(500)
+            .expect(1)
+            .create_async()
+            .await;
+        let rollback = server
+            .mock("POST", "/deployments/dep-1/create_flow_run")
+            .match_body(mockito::Matcher::PartialJsonString(
+                r#"{"parameters":{"config":{"action":"destroy"}}}"#.to_string(),
+            ))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"id":"run-destroy","name":"destroy-run","state_type":"SCHEDULED","state_name":"Scheduled"}"#)
+            .expect(1)
+            .create_async()
+            .await;
+        server
+            .mock("GET", "/flow_runs/run-destroy")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"id":"run-destroy","name":"destroy-run","state_type":"COMPLETED","state_name":"Completed"}"#)
+            .create_async()
+            .await;
+        let pipeline = pipeline(
+            r#"
+[[step]]
+name = "apply"
+deployment = "terraform"
+params = { config = { action = "apply" } }
+
+[[step]]
+name = "rollback"
+deployment = "terraform"
+params = { config = { action = "destroy" } }
+on_failure = ["apply"]
+"#,
+        )
+        .unwrap();
+
+        let err = run(
+            test_client(&server),
+            pipeline,
+            true,
+            TimeDisplay::default(),
+            &Guard::default(),
+            &[],
+        )
+        .await
+        .unwrap_err();
+
+        assert!(
+            matches!(err, PfpError::Api(ref msg) if msg.starts_with("step apply: ")
+                && msg.ends_with("; pipeline steps did not complete: apply (Error)")),
+            "{err:?}"
+        );
+        assert_eq!(err.exit_code(), 2);
+        refused.assert_async().await;
+        rollback.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn read_only_mode_stops_the_pipeline() {
+        let mut server = mockito::Server::new_async().await;
+        mock_resolution(&mut server).await;
+        let create = server
+            .mock("POST", "/deployments/dep-1/create_flow_run")
+            .expect(0)
+            .create_async()
+            .await;
+        let client = PrefectClient::new(Config {
+            api_url: server.url(),
+            auth_header: None,
+            read_only: true,
+        });
+
+        // Independent steps: only the stop keeps `second` from running.
+        let pipeline = pipeline(
+            "[[step]]\nname = \"first\"\ndeployment = \"terraform\"\n\n[[step]]\nname = \"second\"\ndeployment = \"terraform\"\n",
+        )
+        .unwrap();
+
+        let err = run(
+            client,
+            pipeline,
+            true,
+            TimeDisplay::default(),
+            &Guard::default(),
+            &[],
+        )
+        .await
+        .unwrap_err();
+
+        assert!(
+            matches!(err, PfpError::ReadOnly(ref msg)
+                if msg.ends_with("; pipeline steps did not complete: first (Error)")),
+            "{err:?}"
+        );
+        assert_eq!(err.exit_code(), 3);
+        create.assert_async().await;
+    }
+}
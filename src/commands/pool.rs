@@ -1,6 +1,102 @@
 use crate::client::PrefectClient;
 use crate::error::Result;
+use crate::guard::Guard;
+use crate::logger;
+use crate::models::WorkPool;
 use crate::output;
+use serde::Serialize;
+use std::sync::Arc;
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+
+/// Nonterminal run counts requested at once by `pool ls`.
+const COUNT_CONCURRENCY: usize = 8;
+
+/// A work pool with its number of nonterminal flow runs, for `pool ls`.
+#[derive(Debug, Serialize)]
+pub struct PoolOverview {
+    #[serde(flatten)]
+    pub pool: WorkPool,
+    /// `None` when the count could not be fetched
+    pub nonterminal_run_count: Option<u64>,
+}
+
+pub async fn list(client: PrefectClient, json: bool) -> Result<()> {
+    let pools = client.list_work_pools().await?;
+    let counts = count_nonterminal_runs(client, &pools).await;
+    let overviews: Vec<PoolOverview> = pools
+        .into_iter()
+        .zip(counts)
+        .map(|(pool, nonterminal_run_count)| PoolOverview {
+            pool,
+            nonterminal_run_count,
+        })
+        .collect();
+
+    if json {
+        output::print_json(&overviews);
+    } else {
+        print_overviews(&overviews);
+    }
+    Ok(())
+}
+
+/// Count each pool's nonterminal runs with at most `COUNT_CONCURRENCY`
+/// requests in flight. Counts are returned in the order of `pools`; a
+/// failed count is reported and left out.
+async fn count_nonterminal_runs(client: PrefectClient, pools: &[WorkPool]) -> Vec<Option<u64>> {
+    let client = Arc::new(client);
+    let permits = Arc::new(Semaphore::new(COUNT_CONCURRENCY));
+    let mut tasks = JoinSet::new();
+    for (index, pool) in pools.iter().enumerate() {
+        let client = Arc::clone(&client);
+        let permits = Arc::clone(&permits);
+        let name = pool.name.clone();
+        tasks.spawn(async move {
+            let _permit = permits
+                .acquire_owned()
+                .await
+                .expect("semaphore never closed");
+            let count = client
+                .count_nonterminal_flow_runs_for_work_pool(&name)
+                .await;
+            (index, name, count)
+        });
+    }
+
+    let mut counts = vec![None; pools.len()];
+    while let Some(joined) = tasks.join_next().await {
+        match joined {
+            Ok((index, _, Ok(count))) => counts[index] = Some(count),
+            Ok((_, name, Err(e))) => {
+                eprintln!("Warning: could not count flow runs of {}: {}", name, e)
+            }
+            Err(e) => eprintln!("Warning: count task panicked: {}", e),
+        }
+    }
+    counts
+}
+
+fn print_overviews(overviews: &[PoolOverview]) {
+    println!(
+        "{:<30} {:<16} {:<12} {:<8} {:>6} {:>6}",
+        "WORK POOL", "TYPE", "STATUS", "PAUSED", "LIMIT", "RUNS"
+    );
+    for o in overviews {
+        let pool = &o.pool;
+        println!(
+            "{:<30} {:<16} {:<12} {:<8} {:>6} {:>6}",
+            output::truncate(&pool.name, 30),
+            output::truncate(pool.r#type.as_deref().unwrap_or("-"), 16),
+            output::state_color(pool.status.as_deref().unwrap_or("UNKNOWN")),
+            if pool.is_paused { "yes" } else { "no" },
+            pool.concurrency_limit
+                .map_or_else(|| "-".to_string(), |limit| limit.to_string()),
+            o.nonterminal_run_count
+                .map_or_else(|| "?".to_string(), |count| count.to_string()),
+        );
+    }
+}
 
 pub async fn status(client: PrefectClient, name: String, json: bool) -> Result<()> {
     let pool = client.get_work_pool(&name).await?;
@@ -40,8 +136,24 @@ pub async fn assert_idle(client: PrefectClient, name: String, json: bool) -> Res
     }
 }
 
-pub async fn set_paused(client: PrefectClient, name: String, paused: bool) -> Result<()> {
+pub async fn set_paused(
+    client: PrefectClient,
+    name: String,
+    paused: bool,
+    guard: &Guard,
+) -> Result<()> {
+    let request = if paused {
+        "Pause work pool"
+    } else {
+        "Resume work pool"
+    };
+    guard.policy().check_pool(request, &name)?;
+    if paused {
+        guard.confirm_pool(request, &name)?;
+    }
     let pool = client.set_work_pool_paused(&name, paused).await?;
+    logger::record_result("work_pool", pool.name.as_str());
+    logger::record_result("paused", pool.is_paused);
     let action = if paused { "Paused" } else { "Resumed" };
     eprintln!("{action}: {}", pool.name);
     Ok(())
@@ -1,13 +1,23 @@
-use crate::client::PrefectClient;
+use crate::client::{LogFilter, PrefectClient};
 use crate::error::{PfpError, Result};
-use crate::models::FlowRun;
-use crate::output;
+use crate::guard::Guard;
+use crate::logger;
+use crate::models::{self, Deployment, FlowRun, LogEntry};
+use crate::notify;
+use crate::output::{self, OutputFormat};
 use crate::params;
 use crate::resolve;
+use crate::settings::Notifier;
+use crate::timefmt::TimeDisplay;
 use crate::validate;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::BTreeSet;
+use std::io::IsTerminal;
 
 /// Load parameters from a file path, or from stdin if `path` is "-".
-/// Returns a validated JSON object, or PfpError::Config on any failure.
+/// Returns a validated JSON object, or a list of them for a fan-out, or
+/// PfpError::Config on any failure.
 pub fn load_params_file(path: &str) -> Result<serde_json::Value> {
     let content = if path == "-" {
         use std::io::Read;
@@ -21,9 +31,10 @@ pub fn load_params_file(path: &str) -> Result<serde_json::Value> {
             PfpError::Config(format!("Failed to read params file '{}': {}", path, e))
         })?
     };
-    params::parse_params(&content).map_err(PfpError::Config)
+    params::parse_payload(&content).map_err(PfpError::Config)
 }
 
+#[allow(clippy::too_many_arguments)]
 pub async fn run(
     client: PrefectClient,
     query: String,
@@ -31,7 +42,11 @@ pub async fn run(
     sets: Vec<String>,
     tags: Vec<String>,
     params_base: Option<serde_json::Value>,
-    json: bool,
+    format: OutputFormat,
+    time: TimeDisplay,
+    guard: &Guard,
+    notifiers: &[Notifier],
+    secret_paths: &mut BTreeSet<String>,
 ) -> Result<()> {
     let deployment = resolve::resolve_deployment(&client, &query).await?;
     eprintln!("Resolved: {}", deployment.full_name());
@@ -44,12 +59,8 @@ pub async fn run(
         overrides = params::merge_params(&overrides, &set_overrides);
     }
 
-    // Validate overrides against deployment's parameter schema
-    if let Some(schema) = &deployment.parameter_openapi_schema {
-        validate::validate_params(&overrides, schema)?;
-    }
-
-    let parameters = params::merge_params(&deployment.parameters, &overrides);
+    let parameters = checked_parameters(&deployment, &overrides, guard, secret_paths)?;
+    guard.confirm_deployment("Run deployment", &deployment.full_name())?;
 
     // Create flow run
     let run_value = client
@@ -57,9 +68,15 @@ pub async fn run(
         .await?;
     let flow_run: FlowRun =
         serde_json::from_value(run_value.clone()).map_err(|e| PfpError::Api(e.to_string()))?;
+    logger::record_result("flow_run_id", flow_run.id.as_str());
+    logger::record_result("flow_run", flow_run.name.as_str());
 
-    if json && !watch {
-        output::print_json(&run_value);
+    if !watch && format.is_structured() {
+        if format == OutputFormat::Jsonl {
+            output::print_json_line(&run_value);
+        } else {
+            output::print_json(&run_value);
+        }
         return Ok(());
     }
 
@@ -73,61 +90,544 @@ pub async fn run(
         return Ok(());
     }
 
-    // Watch: poll until terminal state
-    let mut last_state = String::new();
+    watch_flow_run(&client, &flow_run.id, format, time, notifiers).await
+}
+
+/// Validate `overrides` against the deployment's parameter schema and check
+/// the run against the policy. Returns the parameters the run is created
+/// with: the deployment defaults with `overrides` merged on top. The
+/// schema's secret fields are added to `secret_paths` first, so the
+/// invocation log masks them even when the check fails.
+pub fn checked_parameters(
+    deployment: &Deployment,
+    overrides: &Value,
+    guard: &Guard,
+    secret_paths: &mut BTreeSet<String>,
+) -> Result<Value> {
+    if let Some(schema) = &deployment.parameter_openapi_schema {
+        secret_paths.extend(validate::secret_paths(schema));
+        validate::validate_params(overrides, schema)?;
+    }
+    let parameters = params::merge_params(&deployment.parameters, overrides);
+    guard
+        .policy()
+        .check_run(&deployment.full_name(), overrides, &parameters)?;
+    Ok(parameters)
+}
+
+/// State shown for a fan-out run the server refused to create.
+const NOT_CREATED: &str = "Not created";
+
+/// State shown for a fan-out run pfp stopped watching.
+const UNKNOWN: &str = "Unknown";
+
+/// Consecutive failed polls of one run before a fan-out watch gives up on
+/// it.
+const MAX_POLL_ERRORS: u32 = 3;
+
+/// One flow run of a fan-out and how it ended. A run that could not be
+/// created has no `flow_run_id` and the error as its `message`.
+#[derive(Debug, Serialize)]
+pub struct RunOutcome {
+    /// The matrix values or payload number that set this run apart
+    pub label: String,
+    pub overrides: Value,
+    pub flow_run_id: Option<String>,
+    pub flow_run: String,
+    pub state: String,
+    pub state_type: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    /// Polling the run kept failing, so its final state is unknown
+    #[serde(skip)]
+    lost: bool,
+}
+
+impl RunOutcome {
+    fn update(&mut self, run: &FlowRun) {
+        self.state = run.state_name.clone();
+        self.state_type = run.state_type.clone();
+        self.message = run.state_message().map(str::to_string);
+    }
+
+    /// Whether the run will not change again, or at least will not be
+    /// polled again: one that was never created or could not be watched.
+    fn is_terminal(&self) -> bool {
+        self.flow_run_id.is_none() || self.lost || models::is_terminal_state(&self.state_type)
+    }
+}
+
+/// Create one flow run of a deployment per `--params-file` payload and
+/// `--matrix` combination. Every run is validated and checked before any
+/// is created. With `watch`, polls all of them and fails if any run does
+/// not complete.
+#[allow(clippy::too_many_arguments)]
+pub async fn run_many(
+    client: PrefectClient,
+    query: String,
+    watch: bool,
+    payloads: Vec<Value>,
+    matrix: Vec<String>,
+    sets: Vec<String>,
+    tags: Vec<String>,
+    format: OutputFormat,
+    guard: &Guard,
+    notifiers: &[Notifier],
+    secret_paths: &mut BTreeSet<String>,
+) -> Result<()> {
+    let deployment = resolve::resolve_deployment(&client, &query).await?;
+    eprintln!("Resolved: {}", deployment.full_name());
+
+    let combinations = params::expand_matrix(&matrix).map_err(PfpError::Config)?;
+    let set_overrides = params::build_params(&sets).map_err(PfpError::Config)?;
+    let payloads = if payloads.is_empty() {
+        vec![Value::Object(serde_json::Map::new())]
+    } else {
+        payloads
+    };
+
+    // payload < matrix values < --set
+    let mut planned = Vec::new();
+    for (index, payload) in payloads.iter().enumerate() {
+        for combination in &combinations {
+            let mut label: Vec<String> = combination.clone();
+            if payloads.len() > 1 {
+                label.insert(0, format!("payload {}", index + 1));
+            }
+            let label = label.join(" ");
+            let matrix_overrides = params::build_params(combination).map_err(PfpError::Config)?;
+            let overrides = params::merge_params(
+                &params::merge_params(payload, &matrix_overrides),
+                &set_overrides,
+            );
+            let parameters = checked_parameters(&deployment, &overrides, guard, secret_paths)
+                .map_err(|e| match e {
+                    PfpError::Validation(msg) => {
+                        PfpError::Validation(format!("{}: {}", label, msg))
+                    }
+                    other => other,
+                })?;
+            planned.push((label, overrides, parameters));
+        }
+    }
+
+    guard.confirm_deployment(
+        &format!("Run deployment {} times", planned.len()),
+        &deployment.full_name(),
+    )?;
+
+    // A failed creation is recorded and the remaining runs are still
+    // created, so the runs that do exist are always reported.
+    let mut outcomes = Vec::new();
+    for (label, overrides, parameters) in planned {
+        let created = client
+            .create_flow_run(&deployment.id, parameters, tags.clone())
+            .await
+            .and_then(|value| {
+                serde_json::from_value::<FlowRun>(value).map_err(|e| PfpError::Api(e.to_string()))
+            });
+        let mut outcome = RunOutcome {
+            label,
+            overrides,
+            flow_run_id: None,
+            flow_run: String::new(),
+            state: NOT_CREATED.to_string(),
+            state_type: String::new(),
+            message: None,
+            lost: false,
+        };
+        match created {
+            Ok(flow_run) => {
+                if !format.is_structured() {
+                    eprintln!(
+                        "Created flow run '{}' ({}) for {}",
+                        flow_run.name,
+                        flow_run.short_id(),
+                        outcome.label
+                    );
+                }
+                outcome.flow_run_id = Some(flow_run.id.clone());
+                outcome.flow_run = flow_run.name.clone();
+                outcome.update(&flow_run);
+            }
+            Err(e) => {
+                if !format.is_structured() {
+                    eprintln!("Failed to create flow run for {}: {}", outcome.label, e);
+                }
+                outcome.message = Some(e.to_string());
+                // A watched --jsonl stream only prints state changes, and
+                // this run will have none.
+                if watch && format == OutputFormat::Jsonl {
+                    output::print_json_line(&outcome);
+                }
+            }
+        }
+        outcomes.push(outcome);
+    }
+    logger::record_result(
+        "flow_run_ids",
+        outcomes
+            .iter()
+            .filter_map(|o| o.flow_run_id.clone())
+            .collect::<Vec<_>>(),
+    );
+
+    if watch {
+        watch_many(&client, &mut outcomes, format, notifiers).await?;
+    }
+
+    match format {
+        OutputFormat::Json => output::print_json(&outcomes),
+        OutputFormat::Jsonl if !watch => output::print_json_lines(&outcomes),
+        _ => {}
+    }
+
+    let labels_where = |keep: fn(&RunOutcome) -> bool| -> Vec<String> {
+        outcomes
+            .iter()
+            .filter(|o| keep(o))
+            .map(|o| o.label.clone())
+            .collect()
+    };
+    let mut problems = Vec::new();
+    for (labels, what) in [
+        (
+            labels_where(|o| o.flow_run_id.is_none()),
+            "could not be created",
+        ),
+        (labels_where(|o| o.lost), "could not be watched"),
+    ] {
+        if !labels.is_empty() {
+            problems.push(format!(
+                "{} of {} flow runs {}: {}",
+                labels.len(),
+                outcomes.len(),
+                what,
+                labels.join(", ")
+            ));
+        }
+    }
+    if !problems.is_empty() {
+        return Err(PfpError::PartialFailure(problems.join("; ")));
+    }
+    if !watch {
+        return Ok(());
+    }
+
+    let failed: Vec<String> = outcomes
+        .iter()
+        .filter(|o| o.state_type != "COMPLETED")
+        .map(|o| format!("{} ({})", o.label, o.state))
+        .collect();
+    if failed.is_empty() {
+        Ok(())
+    } else {
+        Err(PfpError::FlowRunFailed(format!(
+            "{} of {} runs did not complete: {}",
+            failed.len(),
+            outcomes.len(),
+            failed.join(", ")
+        )))
+    }
+}
+
+/// Poll every run until all are terminal. A terminal redraws one status
+/// table in place; other table output prints a line per state change, and
+/// `--jsonl` an outcome object per state change.
+async fn watch_many(
+    client: &PrefectClient,
+    outcomes: &mut [RunOutcome],
+    format: OutputFormat,
+    notifiers: &[Notifier],
+) -> Result<()> {
+    let live = !format.is_structured() && std::io::stdout().is_terminal();
+    let mut drawn_lines = 0;
+    if !format.is_structured() && !live {
+        for outcome in outcomes.iter() {
+            println!(
+                "{} | {}",
+                outcome.label,
+                output::state_color(&outcome.state)
+            );
+        }
+    }
+    let mut poll_errors = vec![0; outcomes.len()];
     loop {
-        let current: FlowRun = serde_json::from_value(client.get_flow_run(&flow_run.id).await?)
-            .map_err(|e| PfpError::Api(e.to_string()))?;
-
-        if current.state_name != last_state {
-            let ts = current
-                .start_time
-                .as_deref()
-                .or(Some(&current.id[..8]))
-                .unwrap_or("-");
-            if json {
-                output::print_json(&serde_json::json!({
-                    "state": current.state_name,
-                    "state_type": current.state_type,
-                    "timestamp": ts
-                }));
-            } else {
-                output::print_watch_state(&current.state_name, ts);
+        for (outcome, errors) in outcomes.iter_mut().zip(poll_errors.iter_mut()) {
+            if outcome.is_terminal() {
+                continue;
+            }
+            let Some(flow_run_id) = &outcome.flow_run_id else {
+                continue;
+            };
+            // A failed poll is retried on the next tick; only a run that
+            // keeps failing is given up on, and the others are still watched.
+            let polled = client.get_flow_run(flow_run_id).await.and_then(|value| {
+                serde_json::from_value::<FlowRun>(value.clone())
+                    .map(|run| (run, value))
+                    .map_err(|e| PfpError::Api(e.to_string()))
+            });
+            let (current, value) = match polled {
+                Ok(polled) => {
+                    *errors = 0;
+                    polled
+                }
+                Err(e) => {
+                    *errors += 1;
+                    if *errors < MAX_POLL_ERRORS {
+                        continue;
+                    }
+                    outcome.lost = true;
+                    outcome.state = UNKNOWN.to_string();
+                    outcome.message = Some(format!("stopped watching: {}", e));
+                    print_outcome_change(outcome, format, live);
+                    continue;
+                }
+            };
+            if current.state_name == outcome.state {
+                continue;
+            }
+            outcome.update(&current);
+            print_outcome_change(outcome, format, live);
+            if current.is_terminal() {
+                notify::notify_all(notifiers, &current, &value).await;
             }
-            last_state = current.state_name.clone();
         }
 
-        if current.is_terminal() {
-            if current.is_success() {
-                return Ok(());
-            } else {
-                return Err(PfpError::FlowRunFailed(format!(
-                    "{} ({})",
-                    current.state_name,
-                    current.short_id()
-                )));
+        if live {
+            if drawn_lines > 0 {
+                // Move back over the previous table and clear it.
+                print!("\x1b[{}A\x1b[J", drawn_lines);
             }
+            drawn_lines = print_outcomes_table(outcomes);
+        }
+
+        if outcomes.iter().all(RunOutcome::is_terminal) {
+            let final_states: serde_json::Map<String, Value> = outcomes
+                .iter()
+                .filter_map(|o| Some((o.flow_run_id.clone()?, o.state.clone().into())))
+                .collect();
+            logger::record_result("final_states", final_states);
+            return Ok(());
         }
 
         tokio::time::sleep(std::time::Duration::from_secs(3)).await;
     }
 }
 
+/// Print one fan-out run's new state, unless a live table shows it.
+fn print_outcome_change(outcome: &RunOutcome, format: OutputFormat, live: bool) {
+    match format {
+        OutputFormat::Jsonl => output::print_json_line(outcome),
+        OutputFormat::Table if !live => {
+            println!(
+                "{} | {}",
+                outcome.label,
+                output::state_color(&outcome.state)
+            )
+        }
+        _ => {}
+    }
+}
+
+/// Print the fan-out status table; returns the number of lines printed.
+fn print_outcomes_table(outcomes: &[RunOutcome]) -> usize {
+    let width = outcomes
+        .iter()
+        .map(|o| o.label.chars().count())
+        .max()
+        .unwrap_or(0)
+        .clamp(5, 40);
+    println!(
+        "{:<width$} {:<26} {:<12} {:<9} MESSAGE",
+        "RUN", "FLOW RUN", "STATE", "ID"
+    );
+    for o in outcomes {
+        println!(
+            "{:<width$} {:<26} {:<12} {:<9} {}",
+            output::truncate(&o.label, width),
+            output::truncate(&o.flow_run, 26),
+            output::state_color(&o.state),
+            o.flow_run_id.as_deref().map_or("-", models::short_id),
+            output::truncate(o.message.as_deref().unwrap_or(""), 60),
+        );
+    }
+    outcomes.len() + 1
+}
+
+/// Number of trailing ERROR-level log lines shown when a watched run fails.
+const FAILURE_LOG_LINES: usize = 10;
+
+/// Poll a flow run until it reaches a terminal state, printing each state
+/// change. A non-successful end state returns `FlowRunFailed` after showing
+/// the state message and the run's last ERROR-level log lines. `notifiers`
+/// are told about the end state either way.
+pub async fn watch_flow_run(
+    client: &PrefectClient,
+    flow_run_id: &str,
+    format: OutputFormat,
+    time: TimeDisplay,
+    notifiers: &[Notifier],
+) -> Result<()> {
+    let current = watch_until_terminal(client, flow_run_id, Some(format), time, notifiers).await?;
+    if current.is_success() {
+        return Ok(());
+    }
+    let mut reason = format!("{} ({})", current.state_name, current.short_id());
+    if let Some(message) = current.state_message() {
+        reason.push_str(&format!(": {}", message));
+    }
+    Err(PfpError::FlowRunFailed(reason))
+}
+
+/// Like `watch_flow_run`, but returns the terminal flow run whatever state
+/// it ended in. With no `progress` format nothing is printed.
+pub async fn watch_until_terminal(
+    client: &PrefectClient,
+    flow_run_id: &str,
+    progress: Option<OutputFormat>,
+    time: TimeDisplay,
+    notifiers: &[Notifier],
+) -> Result<FlowRun> {
+    let mut last_state = String::new();
+    loop {
+        let value = client.get_flow_run(flow_run_id).await?;
+        let current: FlowRun =
+            serde_json::from_value(value.clone()).map_err(|e| PfpError::Api(e.to_string()))?;
+
+        if let Some(format) = progress {
+            print_progress(client, &current, &last_state, format, time).await;
+        }
+        last_state = current.state_name.clone();
+
+        if current.is_terminal() {
+            logger::record_result("final_state", current.state_name.as_str());
+            notify::notify_all(notifiers, &current, &value).await;
+            return Ok(current);
+        }
+
+        tokio::time::sleep(std::time::Duration::from_secs(3)).await;
+    }
+}
+
+/// Print a state change of a watched run; a failed end state comes with its
+/// message and last ERROR-level log lines.
+async fn print_progress(
+    client: &PrefectClient,
+    current: &FlowRun,
+    last_state: &str,
+    format: OutputFormat,
+    time: TimeDisplay,
+) {
+    let failed = current.is_terminal() && !current.is_success();
+    if current.state_name == last_state && !failed {
+        return;
+    }
+    let error_logs = if failed {
+        recent_error_logs(client, &current.id).await
+    } else {
+        Vec::new()
+    };
+    let ts = current
+        .start_time
+        .as_deref()
+        .or(Some(&current.id[..8]))
+        .unwrap_or("-");
+    if format.is_structured() {
+        let mut state = serde_json::json!({
+            "state": current.state_name,
+            "state_type": current.state_type,
+            "timestamp": ts
+        });
+        if failed {
+            state["message"] = serde_json::json!(current.state_message());
+            state["error_logs"] = serde_json::json!(error_logs);
+        }
+        if format == OutputFormat::Jsonl {
+            output::print_json_line(&state);
+        } else {
+            output::print_json(&state);
+        }
+    } else {
+        output::print_watch_state(&current.state_name, ts, time);
+        if let Some(message) = current.state_message().filter(|_| failed) {
+            println!("Message: {}", message);
+        }
+        if !error_logs.is_empty() {
+            println!("Last error logs:");
+            output::print_logs(&error_logs, time);
+        }
+    }
+}
+
+/// Fetch the last ERROR-level log lines of a flow run. Best-effort: a lookup
+/// failure is reported but never replaces the run's own failure.
+async fn recent_error_logs(client: &PrefectClient, flow_run_id: &str) -> Vec<LogEntry> {
+    let filter = LogFilter {
+        min_level: Some(40),
+        ..LogFilter::for_flow_run(flow_run_id)
+    };
+    match client
+        .get_flow_run_logs_tail(&filter, FAILURE_LOG_LINES)
+        .await
+    {
+        Ok(values) => values
+            .into_iter()
+            .filter_map(|v| serde_json::from_value(v).ok())
+            .collect(),
+        Err(e) => {
+            eprintln!("Warning: could not fetch error logs: {}", e);
+            Vec::new()
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::client::PrefectClient;
     use crate::config::Config;
     use crate::error::PfpError;
+    use crate::guard::Guard;
+    use crate::output::OutputFormat;
+    use crate::settings::Notifier;
+    use crate::timefmt::TimeDisplay;
     use serde_json::json;
+    use std::collections::BTreeSet;
 
     fn test_client(server: &mockito::Server) -> PrefectClient {
         let config = Config {
             api_url: server.url(),
             auth_header: Some("Basic dGVzdDp0ZXN0".to_string()),
+            read_only: false,
         };
         PrefectClient::new(config)
     }
 
+    #[test]
+    fn checked_parameters_collects_secret_paths_even_when_validation_fails() {
+        let deployment: crate::models::Deployment = serde_json::from_value(json!({
+            "id": "dep-1",
+            "name": "test-deploy-prod",
+            "parameter_openapi_schema": {
+                "type": "object",
+                "properties": {"dsn": {"type": "string", "format": "password"}}
+            }
+        }))
+        .unwrap();
+        let mut secret_paths = BTreeSet::new();
+
+        let err = super::checked_parameters(
+            &deployment,
+            &json!({"bogus": 1}),
+            &Guard::default(),
+            &mut secret_paths,
+        )
+        .unwrap_err();
+
+        assert!(matches!(err, PfpError::Validation(_)));
+        assert_eq!(secret_paths, BTreeSet::from(["dsn".to_string()]));
+    }
+
     fn mock_deployment_with_schema() -> serde_json::Value {
         json!([{
             "id": "dep-1",
@@ -235,7 +735,11 @@ mod tests {
             ],
             vec![],
             None,
-            false,
+            OutputFormat::Table,
+            TimeDisplay::default(),
+            &Guard::default(),
+            &[],
+            &mut BTreeSet::new(),
         )
         .await;
 
@@ -280,7 +784,11 @@ mod tests {
             vec![],
             vec!["urgent".to_string()],
             None,
-            false,
+            OutputFormat::Table,
+            TimeDisplay::default(),
+            &Guard::default(),
+            &[],
+            &mut BTreeSet::new(),
         )
         .await;
 
@@ -322,7 +830,11 @@ mod tests {
             vec!["config.dry_urn=true".to_string()],
             vec![],
             None,
-            false,
+            OutputFormat::Table,
+            TimeDisplay::default(),
+            &Guard::default(),
+            &[],
+            &mut BTreeSet::new(),
         )
         .await;
 
@@ -381,7 +893,11 @@ mod tests {
             vec!["config.bogus=true".to_string()],
             vec![],
             None,
-            false,
+            OutputFormat::Table,
+            TimeDisplay::default(),
+            &Guard::default(),
+            &[],
+            &mut BTreeSet::new(),
         )
         .await;
 
@@ -425,7 +941,11 @@ mod tests {
             vec![],
             vec![],
             None,
-            false,
+            OutputFormat::Table,
+            TimeDisplay::default(),
+            &Guard::default(),
+            &[],
+            &mut BTreeSet::new(),
         )
         .await;
 
@@ -472,7 +992,11 @@ mod tests {
             vec![],
             vec![],
             Some(json!({"environment": "production", "config": {"action": "destroy"}})),
-            false,
+            OutputFormat::Table,
+            TimeDisplay::default(),
+            &Guard::default(),
+            &[],
+            &mut BTreeSet::new(),
         )
         .await;
 
@@ -513,7 +1037,11 @@ mod tests {
             vec![],
             vec![],
             Some(json!({"config": {"dry_urn": true}})),
-            false,
+            OutputFormat::Table,
+            TimeDisplay::default(),
+            &Guard::default(),
+            &[],
+            &mut BTreeSet::new(),
         )
         .await;
 
@@ -566,7 +1094,11 @@ mod tests {
             vec!["config.action=apply".to_string()],
             vec![],
             Some(json!({"config": {"action": "destroy"}})),
-            false,
+            OutputFormat::Table,
+            TimeDisplay::default(),
+            &Guard::default(),
+            &[],
+            &mut BTreeSet::new(),
         )
         .await;
 
@@ -623,7 +1155,11 @@ mod tests {
                     ]
                 }
             })),
-            false,
+            OutputFormat::Table,
+            TimeDisplay::default(),
+            &Guard::default(),
+            &[],
+            &mut BTreeSet::new(),
         )
         .await;
 
@@ -632,4 +1168,349 @@ mod tests {
         flow_mock.assert_async().await;
         run_mock.assert_async().await;
     }
+
+    async fn mock_resolve(server: &mut mockito::Server, deployments: serde_json::Value) {
+        server
+            .mock("POST", "/deployments/filter")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(deployments.to_string())
+            .create_async()
+            .await;
+        server
+            .mock("POST", "/flows/filter")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"[{"id":"flow-1","name":"test_flow"}]"#)
+            .create_async()
+            .await;
+    }
+
+    #[tokio::test]
+    async fn run_many_matrix_creates_a_run_per_value_and_fails_if_any_fails() {
+        let mut server = mockito::Server::new_async().await;
+        mock_resolve(&mut server, mock_deployment_with_schema()).await;
+        let mut creates = Vec::new();
+        for (env, state_type, state_name) in [
+            ("dev", "COMPLETED", "Completed"),
+            ("prod", "FAILED", "Failed"),
+        ] {
+            let created = server
+                .mock("POST", "/deployments/dep-1/create_flow_run")
+                .match_body(mockito::Matcher::PartialJsonString(format!(
+                    r#"{{"parameters":{{"environment":"{env}","config":{{"action":"plan","dry_run":true}}}}}}"#
+                )))
+                .with_status(200)
+                .with_header("content-type", "application/json")
+                .with_body(format!(
+                    r#"{{"id":"run-{env}","name":"{env}-run","state_type":"SCHEDULED","state_name":"Scheduled"}}"#
+                ))
+                .expect(1)
+                .create_async()
+                .await;
+            creates.push(created);
+            server
+                .mock("GET", format!("/flow_runs/run-{env}").as_str())
+                .with_status(200)
+                .with_header("content-type", "application/json")
+                .with_body(format!(
+                    r#"{{"id":"run-{env}","name":"{env}-run","state_type":"{state_type}","state_name":"{state_name}"}}"#
+                ))
+                .create_async()
+                .await;
+        }
+
+        let err = super::run_many(
+            test_client(&server),
+            "test-deploy".to_string(),
+            true,
+            vec![],
+            vec!["environment=dev,prod".to_string()],
+            vec!["config.dry_run=true".to_string()],
+            vec![],
+            OutputFormat::Json,
+            &Guard::default(),
+            &[],
+            &mut BTreeSet::new(),
+        )
+        .await
+        .unwrap_err();
+
+        assert!(
+            matches!(err, PfpError::FlowRunFailed(ref msg)
+                if msg == "1 of 2 runs did not complete: environment=prod (Failed)"),
+            "{err:?}"
+        );
+        assert_eq!(err.exit_code(), 1);
+        for created in creates {
+            created.assert_async().await;
+        }
+    }
+
+    #[tokio::test]
+    async fn run_many_reports_runs_that_could_not_be_created() {
+        let mut server = mockito::Server::new_async().await;
+        mock_resolve(&mut server, mock_deployment_with_schema()).await;
+        let created = server
+            .mock("POST", "/deployments/dep-1/create_flow_run")
+            .match_body(mockito::Matcher::PartialJsonString(
+                r#"{"parameters":{"environment":"dev"}}"#.to_string(),
+            ))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"id":"run-dev","name":"dev-run","state_type":"SCHEDULED","state_name":"Scheduled"}"#)
+            .expect(1)
+            .create_async()
+            .await;
+        let refused = server
+            .mock("POST", "/deployments/dep-1/create_flow_run")
+            .match_body(mockito::Matcher::PartialJsonString(
+                r#"{"parameters":{"environment":"prod"}}"#.to_string(),
+            ))
+            .with_status(500)
+            .expect(1)
+            .create_async()
+            .await;
+        let watched = server
+            .mock("GET", "/flow_runs/run-dev")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"id":"run-dev","name":"dev-run","state_type":"COMPLETED","state_name":"Completed"}"#)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let err = super::run_many(
+            test_client(&server),
+            "test-deploy".to_string(),
+            true,
+            vec![],
+            vec!["environment=dev,prod".to_string()],
+            vec![],
+            vec![],
+            OutputFormat::Json,
+            &Guard::default(),
+            &[],
+            &mut BTreeSet::new(),
+        )
+        .await
+        .unwrap_err();
+
+        assert!(
+            matches!(err, PfpError::PartialFailure(ref msg)
+                if msg == "1 of 2 flow runs could not be created: environment=prod"),
+            "{err:?}"
+        );
+        assert_eq!(err.exit_code(), 4);
+        created.assert_async().await;
+        refused.assert_async().await;
+        watched.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn run_many_keeps_watching_when_one_run_cannot_be_polled() {
+        let mut server = mockito::Server::new_async().await;
+        mock_resolve(&mut server, mock_deployment_with_schema()).await;
+        for env in ["dev", "prod"] {
+            server
+                .mock("POST", "/deployments/dep-1/create_flow_run")
+                .match_body(mockito::Matcher::PartialJsonString(format!(
+                    r#"{{"parameters":{{"environment":"{env}"}}}}"#
+                )))
+                .with_status(200)
+                .with_header("content-type", "application/json")
+                .with_body(format!(
+                    r#"{{"id":"run-{env}","name":"{env}-run","state_type":"SCHEDULED","state_name":"Scheduled"}}"#
+                ))
+                .create_async()
+                .await;
+        }
+        let watched = server
+            .mock("GET", "/flow_runs/run-dev")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"id":"run-dev","name":"dev-run","state_type":"COMPLETED","state_name":"Completed"}"#)
+            .expect(1)
+            .create_async()
+            .await;
+        let failing = server
+            .mock("GET", "/flow_runs/run-prod")
+            .with_status(503)
+            .expect(super::MAX_POLL_ERRORS as usize)
+            .create_async()
+            .await;
+
+        let err = super::run_many(
+            test_client(&server),
+            "test-deploy".to_string(),
+            true,
+            vec![],
+            vec!["environment=dev,prod".to_string()],
+            vec![],
+            vec![],
+            OutputFormat::Json,
+            &Guard::default(),
+            &[],
+            &mut BTreeSet::new(),
+        )
+        .await
+        .unwrap_err();
+
+        assert!(
+            matches!(err, PfpError::PartialFailure(ref msg)
+                if msg == "1 of 2 flow runs could not be watched: environment=prod"),
+            "{err:?}"
+        );
+        watched.assert_async().await;
+        failing.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn run_many_validates_every_payload_before_creating_runs() {
+        let mut server = mockito::Server::new_async().await;
+        mock_resolve(&mut server, mock_deployment_with_schema()).await;
+        let run_mock = server
+            .mock("POST", "/deployments/dep-1/create_flow_run")
+            .expect(0)
+            .create_async()
+            .await;
+
+        let err = super::run_many(
+            test_client(&server),
+            "test-deploy".to_string(),
+            false,
+            vec![
+                json!({"config": {"action": "plan"}}),
+                json!({"config": {"dry_urn": true}}),
+            ],
+            vec![],
+            vec![],
+            vec![],
+            OutputFormat::Table,
+            &Guard::default(),
+            &[],
+            &mut BTreeSet::new(),
+        )
+        .await
+        .unwrap_err();
+
+        assert!(
+            matches!(err, PfpError::Validation(ref msg) if msg.starts_with("payload 2: ")),
+            "{err:?}"
+        );
+        run_mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn watch_failure_reports_state_message_and_error_logs() {
+        let mut server = mockito::Server::new_async().await;
+        let state_mock = server
+            .mock("GET", "/flow_runs/run-1")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{"id":"run-1aaaaaaa","name":"cool-run","state_type":"FAILED","state_name":"Failed","state":{"type":"FAILED","message":"Flow run encountered an exception: boom"}}"#,
+            )
+            .expect(1)
+            .create_async()
+            .await;
+        let logs_mock = server
+            .mock("POST", "/logs/filter")
+            .match_body(mockito::Matcher::PartialJsonString(
+                r#"{"logs":{"flow_run_id":{"any_":["run-1aaaaaaa"]},"level":{"ge_":40}},"sort":"TIMESTAMP_DESC","limit":10}"#
+                    .to_string(),
+            ))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"[{"level":40,"message":"terraform apply exited 1","timestamp":"2026-01-01T00:00:00Z"}]"#)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let err = super::watch_flow_run(
+            &test_client(&server),
+            "run-1",
+            OutputFormat::Jsonl,
+            TimeDisplay::default(),
+            &[],
+        )
+        .await
+        .unwrap_err();
+
+        assert!(matches!(err, PfpError::FlowRunFailed(ref msg) if msg.contains("boom")));
+        assert_eq!(err.exit_code(), 1);
+        state_mock.assert_async().await;
+        logs_mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn watch_notifies_on_end_state_without_changing_the_outcome() {
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("GET", "/flow_runs/run-1")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"id":"run-1aaaaaaa","name":"cool-run","state_type":"COMPLETED","state_name":"Completed"}"#)
+            .create_async()
+            .await;
+        let hook_mock = server
+            .mock("POST", "/hook")
+            .match_body(mockito::Matcher::PartialJsonString(
+                r#"{"state_name":"Completed"}"#.to_string(),
+            ))
+            .with_status(500)
+            .expect(1)
+            .create_async()
+            .await;
+        let notifiers = [Notifier::Webhook {
+            url: format!("{}/hook", server.url()),
+            headers: Default::default(),
+            timeout: "5s".to_string(),
+        }];
+
+        let result = super::watch_flow_run(
+            &test_client(&server),
+            "run-1",
+            OutputFormat::Table,
+            TimeDisplay::default(),
+            &notifiers,
+        )
+        .await;
+
+        assert!(result.is_ok(), "{:?}", result);
+        hook_mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn watch_failure_survives_error_log_lookup_failure() {
+        let mut server = mockito::Server::new_async().await;
+        let state_mock = server
+            .mock("GET", "/flow_runs/run-1")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"id":"run-1aaaaaaa","name":"cool-run","state_type":"CRASHED","state_name":"Crashed"}"#)
+            .expect(1)
+            .create_async()
+            .await;
+        let logs_mock = server
+            .mock("POST", "/logs/filter")
+            .with_status(500)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let err = super::watch_flow_run(
+            &test_client(&server),
+            "run-1",
+            OutputFormat::Table,
+            TimeDisplay::default(),
+            &[],
+        )
+        .await
+        .unwrap_err();
+
+        assert!(matches!(err, PfpError::FlowRunFailed(ref msg) if msg.starts_with("Crashed")));
+        state_mock.assert_async().await;
+        logs_mock.assert_async().await;
+    }
 }
@@ -1,24 +1,211 @@
-use crate::client::PrefectClient;
-use crate::error::Result;
+use crate::client::{FlowRunFilter, PrefectClient};
+use crate::error::{PfpError, Result};
 use crate::models::FlowRun;
-use crate::output;
+use crate::output::{self, OutputFormat};
 use crate::resolve;
+use crate::timefmt::TimeDisplay;
+use crate::timespec;
 
-pub async fn run(client: PrefectClient, query: String, json: bool) -> Result<()> {
-    let deployment = resolve::resolve_deployment(&client, &query).await?;
-    let values = client.filter_flow_runs(&deployment.id, 10).await?;
+const STATE_TYPES: &[&str] = &[
+    "SCHEDULED",
+    "PENDING",
+    "RUNNING",
+    "COMPLETED",
+    "FAILED",
+    "CANCELLED",
+    "CRASHED",
+    "PAUSED",
+    "CANCELLING",
+];
+
+/// Flow-run selection flags shared by commands that operate on many runs.
+#[derive(Debug, Default, clap::Args)]
+pub struct RunFilterArgs {
+    /// Only runs in these states (comma-separated, e.g. failed,crashed)
+    #[arg(long = "state", value_delimiter = ',')]
+    pub states: Vec<String>,
+    /// Only runs expected to start at or after this time (e.g. 24h, 2026-03-01, RFC 3339)
+    #[arg(long)]
+    pub since: Option<String>,
+    /// Only runs expected to start at or before this time
+    #[arg(long)]
+    pub until: Option<String>,
+    /// Only runs carrying this tag (repeatable; all must match)
+    #[arg(long = "tag", num_args = 1)]
+    pub tags: Vec<String>,
+    /// Only runs submitted to this exact work pool
+    #[arg(long = "work-pool")]
+    pub work_pool: Option<String>,
+}
+
+impl RunFilterArgs {
+    /// Validate the flags and build the API filter. Runs before any network
+    /// request so a typo never costs a deployment lookup.
+    pub fn to_filter(&self) -> Result<FlowRunFilter> {
+        let now = chrono::Utc::now();
+        let parse_bound = |input: &Option<String>| {
+            input
+                .as_deref()
+                .map(|s| timespec::parse_time_bound(s, now))
+                .transpose()
+                .map_err(PfpError::Validation)
+        };
+
+        Ok(FlowRunFilter {
+            ids: Vec::new(),
+            deployment_id: None,
+            state_types: parse_states(&self.states)?,
+            since: parse_bound(&self.since)?,
+            until: parse_bound(&self.until)?,
+            tags: self.tags.clone(),
+            work_pool: self.work_pool.clone(),
+        })
+    }
+}
+
+/// Normalize user-supplied state names (`failed`, `Crashed`) to Prefect
+/// state types.
+pub fn parse_states(states: &[String]) -> Result<Vec<String>> {
+    states
+        .iter()
+        .map(|s| {
+            let upper = s.trim().to_uppercase();
+            if STATE_TYPES.contains(&upper.as_str()) {
+                Ok(upper)
+            } else {
+                Err(PfpError::Validation(format!(
+                    "unknown state '{}', expected one of: {}",
+                    s,
+                    STATE_TYPES.join(", ").to_lowercase()
+                )))
+            }
+        })
+        .collect()
+}
+
+pub async fn run(
+    client: PrefectClient,
+    query: Option<String>,
+    filter_args: RunFilterArgs,
+    limit: usize,
+    queued: bool,
+    format: OutputFormat,
+    time: TimeDisplay,
+) -> Result<()> {
+    let mut filter = filter_args.to_filter()?;
+    let scope = match &query {
+        Some(query) => {
+            let deployment = resolve::resolve_deployment(&client, query).await?;
+            filter.deployment_id = Some(deployment.id.clone());
+            deployment.full_name()
+        }
+        None => "any deployment".to_string(),
+    };
+
+    let values = client.filter_flow_runs(&filter, limit).await?;
     let runs: Vec<FlowRun> = values
         .into_iter()
         .filter_map(|v| serde_json::from_value(v).ok())
         .collect();
 
-    if json {
+    if format == OutputFormat::Json {
         output::print_json(&runs);
+    } else if format == OutputFormat::Jsonl {
+        output::print_json_lines(&runs);
     } else if runs.is_empty() {
-        println!("No flow runs found for {}", deployment.full_name());
+        println!("No flow runs found for {}", scope);
     } else {
-        output::print_flow_runs_table(&runs);
+        output::print_flow_runs_table(&runs, queued, time);
     }
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+
+    fn test_client(server: &mockito::Server) -> PrefectClient {
+        PrefectClient::new(Config {
+            api_url: server.url(),
+            auth_header: Some("Basic dGVzdDp0ZXN0".to_string()),
+            read_only: false,
+        })
+    }
+
+    #[test]
+    fn parse_states_normalizes_case() {
+        let states = parse_states(&["failed".to_string(), "Crashed".to_string()]).unwrap();
+        assert_eq!(states, vec!["FAILED", "CRASHED"]);
+    }
+
+    #[test]
+    fn parse_states_rejects_unknown_state() {
+        let err = parse_states(&["exploded".to_string()]).unwrap_err();
+        assert!(matches!(err, PfpError::Validation(ref msg) if msg.contains("exploded")));
+    }
+
+    #[tokio::test]
+    async fn lists_runs_across_all_deployments_without_query() {
+        let mut server = mockito::Server::new_async().await;
+        let deployments = server
+            .mock("POST", "/deployments/filter")
+            .expect(0)
+            .create_async()
+            .await;
+        let runs = server
+            .mock("POST", "/flow_runs/filter")
+            .match_body(mockito::Matcher::PartialJsonString(
+                r#"{"flow_runs":{"state":{"type":{"any_":["FAILED"]}}},"limit":10}"#.to_string(),
+            ))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"[{"id":"aaa-111","name":"run-1","state_type":"FAILED"}]"#)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let args = RunFilterArgs {
+            states: vec!["failed".to_string()],
+            ..Default::default()
+        };
+        run(
+            test_client(&server),
+            None,
+            args,
+            10,
+            false,
+            OutputFormat::Jsonl,
+            TimeDisplay::default(),
+        )
+        .await
+        .unwrap();
+
+        deployments.assert_async().await;
+        runs.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn invalid_since_is_rejected_before_network() {
+        let server = mockito::Server::new_async().await;
+        let args = RunFilterArgs {
+            since: Some("last tuesday".to_string()),
+            ..Default::default()
+        };
+
+        let err = run(
+            test_client(&server),
+            Some("x".to_string()),
+            args,
+            10,
+            false,
+            OutputFormat::Json,
+            TimeDisplay::default(),
+        )
+        .await
+        .unwrap_err();
+
+        assert!(matches!(err, PfpError::Validation(_)));
+    }
+}
@@ -1,11 +1,16 @@
 use crate::client::PrefectClient;
 use crate::error::{PfpError, Result};
+use crate::guard::Guard;
 use crate::models::DeploymentSchedule;
 use crate::resolve;
 use std::collections::HashSet;
 
-pub async fn run(client: PrefectClient, query: String) -> Result<()> {
+pub async fn run(client: PrefectClient, query: String, guard: &Guard) -> Result<()> {
     let deployment = resolve::resolve_deployment(&client, &query).await?;
+    guard
+        .policy()
+        .check_pause_deployment("Activate schedules of", &deployment.full_name())?;
+    guard.confirm_deployment("Activate schedules of", &deployment.full_name())?;
     let schedules = client.read_deployment_schedules(&deployment.id).await?;
 
     if schedules.is_empty() {
@@ -156,9 +161,14 @@ mod tests {
         PrefectClient::new(Config {
             api_url: server.url(),
             auth_header: Some("Basic dGVzdDp0ZXN0".to_string()),
+            read_only: false,
         })
     }
 
+    fn guard() -> Guard {
+        Guard::new(Default::default(), Default::default(), false)
+    }
+
     async fn mock_resolution(server: &mut mockito::Server) -> (mockito::Mock, mockito::Mock) {
         let deployments = server
             .mock("POST", "/deployments/filter")
@@ -211,7 +221,7 @@ mod tests {
             .create_async()
             .await;
 
-        run(test_client(&server), "backup-prod".to_string())
+        run(test_client(&server), "backup-prod".to_string(), &guard())
             .await
             .unwrap();
 
@@ -235,7 +245,7 @@ mod tests {
             .create_async()
             .await;
 
-        let error = run(test_client(&server), "backup-prod".to_string())
+        let error = run(test_client(&server), "backup-prod".to_string(), &guard())
             .await
             .unwrap_err();
 
@@ -307,7 +317,7 @@ mod tests {
             .create_async()
             .await;
 
-        let error = run(test_client(&server), "backup-prod".to_string())
+        let error = run(test_client(&server), "backup-prod".to_string(), &guard())
             .await
             .unwrap_err();
 
@@ -363,7 +373,7 @@ mod tests {
             .create_async()
             .await;
 
-        let error = run(test_client(&server), "backup-prod".to_string())
+        let error = run(test_client(&server), "backup-prod".to_string(), &guard())
             .await
             .unwrap_err();
         let message = error.to_string();
@@ -376,4 +386,30 @@ mod tests {
         rollback.assert_async().await;
         final_state.assert_async().await;
     }
+
+    #[tokio::test]
+    async fn policy_denial_leaves_schedules_untouched() {
+        let mut server = mockito::Server::new_async().await;
+        let (_deployments, _flows) = mock_resolution(&mut server).await;
+        let schedules = server
+            .mock("GET", "/deployments/dep-1/schedules")
+            .expect(0)
+            .create_async()
+            .await;
+
+        let policy: crate::policy::Policy =
+            toml::from_str("[pause]\ndeployments = [\"*-dev\"]\n").unwrap();
+        let error = run(
+            test_client(&server),
+            "backup-prod".to_string(),
+            &guard().with_policy(policy),
+        )
+        .await
+        .unwrap_err();
+
+        assert!(
+            matches!(error, PfpError::PolicyDenied(ref msg) if msg.contains("backup/backup-prod"))
+        );
+        schedules.assert_async().await;
+    }
 }
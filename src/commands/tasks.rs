@@ -0,0 +1,78 @@
+use crate::client::PrefectClient;
+use crate::error::Result;
+use crate::models::TaskRun;
+use crate::output;
+use crate::resolve;
+use crate::timefmt::TimeDisplay;
+
+pub async fn run(
+    client: PrefectClient,
+    flow_run_id: String,
+    json: bool,
+    time: TimeDisplay,
+) -> Result<()> {
+    let resolved_id = resolve::resolve_flow_run(&client, &flow_run_id).await?;
+    let values = client.filter_task_runs(&resolved_id).await?;
+
+    if json {
+        output::print_json(&values);
+        return Ok(());
+    }
+
+    let tasks: Vec<TaskRun> = values
+        .into_iter()
+        .filter_map(|v| serde_json::from_value(v).ok())
+        .collect();
+
+    if tasks.is_empty() {
+        println!("No task runs found for flow run {}", resolved_id);
+    } else {
+        output::print_task_runs_table(&tasks, time);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+
+    fn test_client(server: &mockito::Server) -> PrefectClient {
+        PrefectClient::new(Config {
+            api_url: server.url(),
+            auth_header: Some("Basic dGVzdDp0ZXN0".to_string()),
+            read_only: false,
+        })
+    }
+
+    #[tokio::test]
+    async fn lists_task_runs_of_exact_flow_run() {
+        let mut server = mockito::Server::new_async().await;
+        let flow_run_id = "aaaaaaaa-bbbb-cccc-dddd-eeeeeeeeeeee";
+        let request = server
+            .mock("POST", "/task_runs/filter")
+            .match_body(mockito::Matcher::PartialJsonString(format!(
+                r#"{{"task_runs":{{"flow_run_id":{{"any_":["{flow_run_id}"]}}}}}}"#
+            )))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"[{"id":"task-1","name":"terraform_apply-0","state_type":"FAILED","state_name":"Failed","state":{"message":"boom"}}]"#,
+            )
+            .expect(1)
+            .create_async()
+            .await;
+
+        run(
+            test_client(&server),
+            flow_run_id.to_string(),
+            false,
+            TimeDisplay::default(),
+        )
+        .await
+        .unwrap();
+
+        request.assert_async().await;
+    }
+}
@@ -0,0 +1,411 @@
+use crate::client::{FlowRunFilter, PrefectClient};
+use crate::error::{PfpError, Result};
+use crate::logger;
+use crate::models::FlowRun;
+use crate::notify;
+use crate::output;
+use crate::settings::Notifier;
+use crate::timespec;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet};
+
+const POLL_SECS: u64 = 3;
+
+/// Where one waited-for run ended up.
+#[derive(Debug, Serialize, PartialEq)]
+pub struct WaitResult {
+    pub flow_run_id: String,
+    pub flow_run: String,
+    pub state: String,
+    pub state_type: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+impl From<&FlowRun> for WaitResult {
+    fn from(run: &FlowRun) -> Self {
+        Self {
+            flow_run_id: run.id.clone(),
+            flow_run: run.name.clone(),
+            state: run.state_name.clone(),
+            state_type: run.state_type.clone(),
+            message: run.state_message().map(str::to_string),
+        }
+    }
+}
+
+/// Block until every run (or, with `any`, the first) reaches a terminal
+/// state. All runs are polled with a single filter request, and `notifiers`
+/// fire once for each run as it finishes. Fails if a run that finished did
+/// not complete, or with `Timeout` once `timeout` passes.
+pub async fn run(
+    client: PrefectClient,
+    ids: Vec<String>,
+    any: bool,
+    timeout: Option<String>,
+    json: bool,
+    notifiers: &[Notifier],
+) -> Result<()> {
+    let ids = if ids.is_empty() {
+        read_stdin_ids()?
+    } else {
+        ids
+    };
+    let ids = dedup(ids);
+    if ids.is_empty() {
+        return Err(PfpError::Validation(
+            "give flow run IDs as arguments or on stdin".to_string(),
+        ));
+    }
+    let timeout = timeout
+        .as_deref()
+        .map(|t| {
+            timespec::parse_duration(t)
+                .and_then(|d| d.to_std().map_err(|e| e.to_string()))
+                .map_err(PfpError::Validation)
+        })
+        .transpose()?;
+    let deadline = timeout.map(|t| tokio::time::Instant::now() + t);
+
+    let filter = FlowRunFilter {
+        ids: ids.clone(),
+        ..Default::default()
+    };
+    let mut last_states: BTreeMap<String, String> = BTreeMap::new();
+    let mut notified: BTreeSet<String> = BTreeSet::new();
+    loop {
+        let (runs, values): (Vec<FlowRun>, Vec<Value>) =
+            poll(&client, &filter, &ids).await?.into_iter().unzip();
+        if !json {
+            for run in &runs {
+                if last_states.get(&run.id) != Some(&run.state_name) {
+                    println!(
+                        "{} {} | {}",
+                        run.short_id(),
+                        output::truncate(&run.name, 26),
+                        output::state_color(&run.state_name)
+                    );
+                }
+            }
+        }
+        for run in &runs {
+            last_states.insert(run.id.clone(), run.state_name.clone());
+        }
+        for (run, value) in runs.iter().zip(&values) {
+            if run.is_terminal() && notified.insert(run.id.clone()) {
+                notify::notify_all(notifiers, run, value).await;
+            }
+        }
+
+        let finished: Vec<&FlowRun> = runs.iter().filter(|r| r.is_terminal()).collect();
+        let done = if any {
+            !finished.is_empty()
+        } else {
+            finished.len() == runs.len()
+        };
+        if done {
+            let final_states: serde_json::Map<String, Value> = finished
+                .iter()
+                .map(|r| (r.id.clone(), Value::from(r.state_name.as_str())))
+                .collect();
+            logger::record_result("final_states", final_states);
+            if json {
+                let results: Vec<WaitResult> = runs.iter().map(WaitResult::from).collect();
+                output::print_json(&results);
+            }
+            return outcome(&finished);
+        }
+
+        let pending: Vec<&FlowRun> = runs.iter().filter(|r| !r.is_terminal()).collect();
+        let mut sleep = std::time::Duration::from_secs(POLL_SECS);
+        if let Some(deadline) = deadline {
+            let now = tokio::time::Instant::now();
+            if now >= deadline {
+                return Err(PfpError::Timeout(format!(
+                    "{} of {} flow runs still not finished: {}",
+                    pending.len(),
+                    runs.len(),
+                    describe(&pending)
+                )));
+            }
+            sleep = sleep.min(deadline - now);
+        }
+        tokio::time::sleep(sleep).await;
+    }
+}
+
+/// Fetch every run in one request, in the order the IDs were given, each
+/// with the API object it was read from. An ID the server does not know is
+/// an error.
+async fn poll(
+    client: &PrefectClient,
+    filter: &FlowRunFilter,
+    ids: &[String],
+) -> Result<Vec<(FlowRun, Value)>> {
+    let mut by_id: BTreeMap<String, (FlowRun, Value)> = client
+        .filter_flow_runs(filter, ids.len())
+        .await?
+        .into_iter()
+        .filter_map(|v| {
+            let run = serde_json::from_value::<FlowRun>(v.clone()).ok()?;
+            Some((run.id.clone(), (run, v)))
+        })
+        .collect();
+    ids.iter()
+        .map(|id| {
+            by_id
+                .remove(id)
+                .ok_or_else(|| PfpError::NoMatch(format!("no flow run with ID '{}'", id)))
+        })
+        .collect()
+}
+
+fn outcome(finished: &[&FlowRun]) -> Result<()> {
+    let failed: Vec<&FlowRun> = finished
+        .iter()
+        .copied()
+        .filter(|r| !r.is_success())
+        .collect();
+    if failed.is_empty() {
+        Ok(())
+    } else {
+        Err(PfpError::FlowRunFailed(format!(
+            "{} of {} finished flow runs did not complete: {}",
+            failed.len(),
+            finished.len(),
+            describe(&failed)
+        )))
+    }
+}
+
+fn describe(runs: &[&FlowRun]) -> String {
+    runs.iter()
+        .map(|r| format!("{} ({})", r.short_id(), r.state_name))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn read_stdin_ids() -> Result<Vec<String>> {
+    use std::io::Read;
+    let mut input = String::new();
+    std::io::stdin()
+        .read_to_string(&mut input)
+        .map_err(|e| PfpError::Config(format!("Failed to read flow run IDs from stdin: {}", e)))?;
+    Ok(ids_from_text(&input))
+}
+
+/// Flow run IDs in text read from stdin: the output of `pfp run --json` or
+/// `--jsonl` (the `id` or `flow_run_id` of each object), or plain IDs
+/// separated by whitespace.
+fn ids_from_text(input: &str) -> Vec<String> {
+    fn collect(value: &Value, out: &mut Vec<String>) {
+        match value {
+            Value::Array(items) => items.iter().for_each(|item| collect(item, out)),
+            Value::Object(map) => {
+                if let Some(Value::String(id)) = map.get("flow_run_id").or_else(|| map.get("id")) {
+                    out.push(id.clone());
+                }
+            }
+            _ => {}
+        }
+    }
+    let values: std::result::Result<Vec<Value>, _> = serde_json::Deserializer::from_str(input)
+        .into_iter()
+        .collect();
+    match values {
+        Ok(values) if values.iter().all(|v| v.is_object() || v.is_array()) => {
+            let mut ids = Vec::new();
+            values.iter().for_each(|v| collect(v, &mut ids));
+            ids
+        }
+        _ => input.split_whitespace().map(str::to_string).collect(),
+    }
+}
+
+fn dedup(ids: Vec<String>) -> Vec<String> {
+    let mut seen = std::collections::BTreeSet::new();
+    ids.into_iter()
+        .filter(|id| seen.insert(id.clone()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use serde_json::json;
+
+    fn test_client(server: &mockito::Server) -> PrefectClient {
+        PrefectClient::new(Config {
+            api_url: server.url(),
+            auth_header: Some("Basic dGVzdDp0ZXN0".to_string()),
+            read_only: false,
+        })
+    }
+
+    async fn mock_runs(server: &mut mockito::Server, runs: Value) -> mockito::Mock {
+        server
+            .mock("POST", "/flow_runs/filter")
+            .match_body(mockito::Matcher::PartialJson(
+                json!({"flow_runs": {"id": {"any_": ["run-a", "run-b"]}}, "limit": 2}),
+            ))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(runs.to_string())
+            .expect(1)
+            .create_async()
+            .await
+    }
+
+    fn ids() -> Vec<String> {
+        vec!["run-a".to_string(), "run-b".to_string()]
+    }
+
+    #[test]
+    fn ids_from_json_jsonl_or_plain_text() {
+        assert_eq!(
+            ids_from_text(r#"{"id": "run-a", "name": "x"}"#),
+            vec!["run-a"]
+        );
+        assert_eq!(
+            ids_from_text("{\"flow_run_id\": \"run-a\"}\n{\"flow_run_id\": \"run-b\"}\n"),
+            vec!["run-a", "run-b"]
+        );
+        assert_eq!(
+            ids_from_text(r#"[{"flow_run_id": "run-a", "label": "x"}]"#),
+            vec!["run-a"]
+        );
+        assert_eq!(
+            ids_from_text("run-a\nrun-b run-c\n"),
+            vec!["run-a", "run-b", "run-c"]
+        );
+        assert_eq!(
+            dedup(vec!["a".into(), "b".into(), "a".into()]),
+            vec!["a", "b"]
+        );
+    }
+
+    #[tokio::test]
+    async fn all_fails_when_any_run_did_not_complete() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = mock_runs(
+            &mut server,
+            json!([
+                {"id": "run-b", "name": "b", "state_type": "CRASHED", "state_name": "Crashed"},
+                {"id": "run-a", "name": "a", "state_type": "COMPLETED", "state_name": "Completed"}
+            ]),
+        )
+        .await;
+
+        let err = run(test_client(&server), ids(), false, None, true, &[])
+            .await
+            .unwrap_err();
+
+        assert!(
+            matches!(err, PfpError::FlowRunFailed(ref msg)
+                if msg == "1 of 2 finished flow runs did not complete: run-b (Crashed)"),
+            "{err:?}"
+        );
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn any_returns_once_one_run_completes() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = mock_runs(
+            &mut server,
+            json!([
+                {"id": "run-a", "name": "a", "state_type": "RUNNING", "state_name": "Running"},
+                {"id": "run-b", "name": "b", "state_type": "COMPLETED", "state_name": "Completed"}
+            ]),
+        )
+        .await;
+
+        assert!(run(test_client(&server), ids(), true, None, false, &[])
+            .await
+            .is_ok());
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn notifies_each_finished_run_with_its_api_object() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = mock_runs(
+            &mut server,
+            json!([
+                {"id": "run-a", "name": "a", "state_type": "RUNNING", "state_name": "Running"},
+                {"id": "run-b", "name": "b", "state_type": "COMPLETED", "state_name": "Completed", "tags": ["manual"]}
+            ]),
+        )
+        .await;
+        let hook = server
+            .mock("POST", "/hook")
+            .match_body(mockito::Matcher::PartialJson(
+                json!({"id": "run-b", "tags": ["manual"]}),
+            ))
+            .with_status(204)
+            .expect(1)
+            .create_async()
+            .await;
+        let notifiers = [Notifier::Webhook {
+            url: format!("{}/hook", server.url()),
+            headers: Default::default(),
+            timeout: "5s".to_string(),
+        }];
+
+        run(test_client(&server), ids(), true, None, true, &notifiers)
+            .await
+            .unwrap();
+
+        mock.assert_async().await;
+        hook.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn times_out_with_pending_runs() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = mock_runs(
+            &mut server,
+            json!([
+                {"id": "run-a", "name": "a", "state_type": "RUNNING", "state_name": "Running"},
+                {"id": "run-b", "name": "b", "state_type": "COMPLETED", "state_name": "Completed"}
+            ]),
+        )
+        .await;
+
+        let err = run(
+            test_client(&server),
+            ids(),
+            false,
+            Some("0s".to_string()),
+            true,
+            &[],
+        )
+        .await
+        .unwrap_err();
+
+        assert!(
+            matches!(err, PfpError::Timeout(ref msg) if msg.contains("run-a (Running)")),
+            "{err:?}"
+        );
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn unknown_id_is_an_error() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = mock_runs(
+            &mut server,
+            json!([{"id": "run-a", "name": "a", "state_type": "RUNNING", "state_name": "Running"}]),
+        )
+        .await;
+
+        let err = run(test_client(&server), ids(), false, None, true, &[])
+            .await
+            .unwrap_err();
+
+        assert!(matches!(err, PfpError::NoMatch(ref msg) if msg.contains("run-b")));
+        mock.assert_async().await;
+    }
+}
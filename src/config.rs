@@ -1,12 +1,17 @@
 use crate::error::{PfpError, Result};
+use crate::logger;
 use base64::Engine;
 use serde::Deserialize;
 use std::path::PathBuf;
 
+const READ_ONLY_ENV: &str = "PFP_READ_ONLY";
+
 #[derive(Debug)]
 pub struct Config {
     pub api_url: String,
     pub auth_header: Option<String>,
+    /// Reject every request that could change server state
+    pub read_only: bool,
 }
 
 #[derive(Deserialize)]
@@ -21,27 +26,45 @@ struct Profile {
     api_url: Option<String>,
     #[serde(rename = "PREFECT_API_AUTH_STRING")]
     auth_string: Option<String>,
+    #[serde(default)]
+    read_only: bool,
 }
 
 impl Config {
+    /// Load the connection settings. `PFP_READ_ONLY` turns read-only mode on
+    /// in addition to the selected profile's `read_only`; it can never turn
+    /// a read-only profile writable.
     pub fn load(server: Option<&str>) -> Result<Self> {
+        let (mut config, profile) = Self::load_connection(server)?;
+        config.read_only |= Self::read_only_from_env()?;
+        logger::record_result("server", config.api_url.as_str());
+        logger::record_result("profile", profile);
+        Ok(config)
+    }
+
+    /// The connection settings and the name of the profile they came from,
+    /// if any.
+    fn load_connection(server: Option<&str>) -> Result<(Self, Option<String>)> {
         // An explicit server is an atomic profile selection: URL and auth must
         // come from the same profile. A process-wide credential must never be
         // silently paired with a different server's URL.
         if let Some(server) = server {
             let profiles = Self::read_profiles()?;
-            return Self::from_profile(&profiles, server);
+            let config = Self::from_profile(&profiles, server)?;
+            return Ok((config, Some(server.to_string())));
         }
 
         // Preserve the established environment-first behavior when no server
         // is selected. The two environment values remain one explicit pair.
         if let Ok(url) = std::env::var("PREFECT_API_URL") {
-            return Ok(Self {
+            let config = Self {
                 api_url: url,
                 auth_header: Self::encode_auth(
                     std::env::var("PREFECT_API_AUTH_STRING").ok().as_deref(),
                 ),
-            });
+                read_only: false,
+            };
+            return Ok((config, None));
         }
 
         // Otherwise the active profile supplies both URL and auth. Retain the
@@ -52,7 +75,7 @@ impl Config {
         if let Ok(auth_string) = std::env::var("PREFECT_API_AUTH_STRING") {
             config.auth_header = Self::encode_auth(Some(&auth_string));
         }
-        Ok(config)
+        Ok((config, Some(active.to_string())))
     }
 
     fn from_profile(profiles: &ProfilesFile, profile_name: &str) -> Result<Self> {
@@ -65,9 +88,22 @@ impl Config {
         Ok(Self {
             api_url: profile.api_url.clone().ok_or(PfpError::NoApiUrl)?,
             auth_header: Self::encode_auth(profile.auth_string.as_deref()),
+            read_only: profile.read_only,
         })
     }
 
+    fn read_only_from_env() -> Result<bool> {
+        match std::env::var(READ_ONLY_ENV) {
+            Ok(value) => parse_flag(&value).ok_or_else(|| {
+                PfpError::Config(format!(
+                    "{}={:?} is not a boolean (use 1/0, true/false, yes/no)",
+                    READ_ONLY_ENV, value
+                ))
+            }),
+            Err(_) => Ok(false),
+        }
+    }
+
     fn encode_auth(auth_string: Option<&str>) -> Option<String> {
         let auth_string = auth_string?;
         let encoded = base64::engine::general_purpose::STANDARD.encode(auth_string.as_bytes());
@@ -90,6 +126,14 @@ impl Config {
     }
 }
 
+fn parse_flag(value: &str) -> Option<bool> {
+    match value.trim().to_lowercase().as_str() {
+        "1" | "true" | "yes" | "on" => Some(true),
+        "" | "0" | "false" | "no" | "off" => Some(false),
+        _ => None,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -150,6 +194,52 @@ PREFECT_API_AUTH_STRING = "norma:secret"
         );
     }
 
+    #[test]
+    #[serial]
+    fn read_only_env_adds_to_profile_setting() {
+        unsafe {
+            std::env::set_var("PREFECT_API_URL", "https://test.example.com/api");
+            std::env::set_var("PFP_READ_ONLY", "1");
+        }
+        let result = Config::load(None);
+        unsafe {
+            std::env::set_var("PFP_READ_ONLY", "maybe");
+        }
+        let invalid = Config::load(None);
+        unsafe {
+            std::env::remove_var("PREFECT_API_URL");
+            std::env::remove_var("PFP_READ_ONLY");
+        }
+        assert!(result.unwrap().read_only);
+        assert!(matches!(invalid, Err(PfpError::Config(ref msg)) if msg.contains("PFP_READ_ONLY")));
+    }
+
+    #[test]
+    fn profile_read_only_setting() {
+        let profiles: ProfilesFile = toml::from_str(
+            r#"[profiles.agent]
+PREFECT_API_URL = "https://agent.example/api"
+read_only = true
+
+[profiles.admin]
+PREFECT_API_URL = "https://admin.example/api"
+"#,
+        )
+        .unwrap();
+
+        assert!(Config::from_profile(&profiles, "agent").unwrap().read_only);
+        assert!(!Config::from_profile(&profiles, "admin").unwrap().read_only);
+    }
+
+    #[test]
+    fn parse_flag_accepts_common_spellings() {
+        assert_eq!(parse_flag("TRUE"), Some(true));
+        assert_eq!(parse_flag(" yes "), Some(true));
+        assert_eq!(parse_flag("0"), Some(false));
+        assert_eq!(parse_flag(""), Some(false));
+        assert_eq!(parse_flag("2"), None);
+    }
+
     #[test]
     fn profiles_path_ends_with_expected() {
         let path = Config::profiles_path();
@@ -11,6 +11,9 @@ pub enum PfpError {
     #[error("Config error: {0}")]
     Config(String),
 
+    #[error("I/O error: {0}")]
+    Io(String),
+
     #[error("No PREFECT_API_URL found in profile")]
     NoApiUrl,
 
@@ -23,16 +26,36 @@ pub enum PfpError {
     #[error("Flow run failed: {0}")]
     FlowRunFailed(String),
 
+    #[error("Timed out: {0}")]
+    Timeout(String),
+
+    #[error("Read-only mode: refusing {0}")]
+    ReadOnly(String),
+
+    #[error("Denied by policy: {0}")]
+    PolicyDenied(String),
+
+    /// Prefect's orchestration refused a proposed state change.
+    #[error("Prefect did not accept {0}")]
+    NotAccepted(String),
+
+    #[error("Partly failed: {0}")]
+    PartialFailure(String),
+
     #[error("{0}")]
     #[allow(dead_code)]
     Validation(String),
 }
 
 impl PfpError {
-    /// Exit code: 1 for flow run failures, 2 for CLI/usage errors.
+    /// Exit code: 1 for flow run failures, 3 for actions refused by
+    /// read-only mode or the policy file, 4 when a bulk action failed for
+    /// some of its targets, 2 for CLI/usage errors.
     pub fn exit_code(&self) -> i32 {
         match self {
             PfpError::FlowRunFailed(_) => 1,
+            PfpError::ReadOnly(_) | PfpError::PolicyDenied(_) => 3,
+            PfpError::PartialFailure(_) => 4,
             _ => 2,
         }
     }
@@ -62,6 +85,18 @@ mod tests {
         assert_eq!(err.exit_code(), 2);
     }
 
+    #[test]
+    fn exit_code_read_only() {
+        let err = PfpError::ReadOnly("PATCH /deployments/abc".to_string());
+        assert_eq!(err.exit_code(), 3);
+    }
+
+    #[test]
+    fn exit_code_partial_failure() {
+        let err = PfpError::PartialFailure("1 of 3 cancellations failed".to_string());
+        assert_eq!(err.exit_code(), 4);
+    }
+
     #[test]
     fn no_match_displays_message() {
         let err = PfpError::NoMatch("no flow run matching 'abc123'".to_string());
@@ -0,0 +1,369 @@
+use crate::error::{PfpError, Result};
+use crate::models::LogEntry;
+use crate::output;
+use crate::timefmt::TimeDisplay;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+/// File formats for `pfp logs --output`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum LogFileFormat {
+    /// The same lines `pfp logs` prints, without colour
+    Text,
+    /// One JSON object per line
+    Jsonl,
+    Csv,
+    /// A standalone page with levels coloured
+    Html,
+}
+
+impl LogFileFormat {
+    /// Guess the format from the file extension, ignoring a trailing `.gz`.
+    pub fn from_path(path: &Path) -> Self {
+        let name = path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+        let name = name.strip_suffix(".gz").unwrap_or(&name);
+        match name.rsplit_once('.').map(|(_, ext)| ext) {
+            Some("jsonl" | "ndjson") => Self::Jsonl,
+            Some("csv") => Self::Csv,
+            Some("html" | "htm") => Self::Html,
+            _ => Self::Text,
+        }
+    }
+}
+
+enum Sink {
+    Plain(BufWriter<File>),
+    Gzip(GzEncoder<BufWriter<File>>),
+}
+
+impl Write for Sink {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        match self {
+            Sink::Plain(w) => w.write(buf),
+            Sink::Gzip(w) => w.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        match self {
+            Sink::Plain(w) => w.flush(),
+            Sink::Gzip(w) => w.flush(),
+        }
+    }
+}
+
+/// A log export file. One-shot exports are written to a temporary sibling
+/// and renamed into place by `finish`, so the path never holds a partial
+/// file. Streaming exports (`--follow`) write to the path directly and flush
+/// after every batch so the file can be tailed while the run is going.
+pub struct LogFile {
+    format: LogFileFormat,
+    sink: Option<Sink>,
+    path: PathBuf,
+    tmp_path: Option<PathBuf>,
+    entries: usize,
+    /// How text exports show timestamps
+    time: TimeDisplay,
+}
+
+impl LogFile {
+    pub fn create(
+        path: &Path,
+        format: LogFileFormat,
+        gzip: bool,
+        streaming: bool,
+        time: TimeDisplay,
+    ) -> Result<Self> {
+        let tmp_path = (!streaming).then(|| {
+            let name = path
+                .file_name()
+                .map(|n| n.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            path.with_file_name(format!(".{}.{}.tmp", name, std::process::id()))
+        });
+        let target = tmp_path.as_deref().unwrap_or(path);
+        let file = File::create(target).map_err(|e| io_error(path, e))?;
+        let writer = BufWriter::new(file);
+        let sink = if gzip {
+            Sink::Gzip(GzEncoder::new(writer, Compression::default()))
+        } else {
+            Sink::Plain(writer)
+        };
+
+        let mut log_file = LogFile {
+            format,
+            sink: Some(sink),
+            path: path.to_path_buf(),
+            tmp_path,
+            entries: 0,
+            time,
+        };
+        let header = match format {
+            LogFileFormat::Csv => {
+                "timestamp,level,flow_run_id,flow_run_name,task_run_id,logger,message\n"
+            }
+            LogFileFormat::Html => HTML_HEADER,
+            LogFileFormat::Text | LogFileFormat::Jsonl => "",
+        };
+        log_file.write_str(header)?;
+        Ok(log_file)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn write_batch(&mut self, logs: &[LogEntry]) -> Result<()> {
+        let mut chunk = String::new();
+        for log in logs {
+            chunk.push_str(&render(log, self.format, self.time));
+            chunk.push('\n');
+        }
+        self.write_str(&chunk)?;
+        self.entries += logs.len();
+        if self.tmp_path.is_none() {
+            let path = self.path.clone();
+            self.sink
+                .as_mut()
+                .expect("sink present until finish")
+                .flush()
+                .map_err(|e| io_error(&path, e))?;
+        }
+        Ok(())
+    }
+
+    /// Complete the file and move it into place. Returns the number of
+    /// entries written.
+    pub fn finish(mut self) -> Result<usize> {
+        if self.format == LogFileFormat::Html {
+            self.write_str(HTML_FOOTER)?;
+        }
+        let sink = self.sink.take().expect("sink present until finish");
+        let writer = match sink {
+            Sink::Plain(w) => w,
+            Sink::Gzip(w) => w.finish().map_err(|e| io_error(&self.path, e))?,
+        };
+        let file = writer
+            .into_inner()
+            .map_err(|e| io_error(&self.path, e.into_error()))?;
+        file.sync_all().map_err(|e| io_error(&self.path, e))?;
+
+        if let Some(tmp) = self.tmp_path.take() {
+            std::fs::rename(&tmp, &self.path).map_err(|e| io_error(&self.path, e))?;
+        }
+        Ok(self.entries)
+    }
+
+    fn write_str(&mut self, s: &str) -> Result<()> {
+        let path = self.path.clone();
+        self.sink
+            .as_mut()
+            .expect("sink present until finish")
+            .write_all(s.as_bytes())
+            .map_err(|e| io_error(&path, e))
+    }
+}
+
+impl Drop for LogFile {
+    /// An export abandoned before `finish` leaves no temporary file behind.
+    fn drop(&mut self) {
+        if let Some(tmp) = &self.tmp_path {
+            let _ = std::fs::remove_file(tmp);
+        }
+    }
+}
+
+fn io_error(path: &Path, e: std::io::Error) -> PfpError {
+    PfpError::Io(format!("{}: {}", path.display(), e))
+}
+
+fn render(log: &LogEntry, format: LogFileFormat, time: TimeDisplay) -> String {
+    match format {
+        LogFileFormat::Text => output::log_line(log, false, time),
+        LogFileFormat::Jsonl => serde_json::to_string(log).unwrap(),
+        LogFileFormat::Csv => [
+            log.timestamp.as_str(),
+            log.level_name(),
+            log.flow_run_id.as_deref().unwrap_or(""),
+            log.flow_run_name.as_deref().unwrap_or(""),
+            log.task_run_id.as_deref().unwrap_or(""),
+            log.name.as_deref().unwrap_or(""),
+            log.message.as_str(),
+        ]
+        .map(csv_field)
+        .join(","),
+        LogFileFormat::Html => format!(
+            r#"<tr class="{}"><td>{}</td><td>{}</td><td>{}</td><td><pre>{}</pre></td></tr>"#,
+            log.level_name().to_lowercase(),
+            html_escape(&log.timestamp),
+            log.level_name(),
+            html_escape(log.flow_run_name.as_deref().unwrap_or("")),
+            html_escape(&log.message),
+        ),
+    }
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn html_escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+const HTML_HEADER: &str = r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<title>pfp logs</title>
+<style>
+body { font-family: monospace; }
+td { padding: 0 0.6em; vertical-align: top; }
+pre { margin: 0; white-space: pre-wrap; }
+.critical, .error { color: #c62828; }
+.warning { color: #ef6c00; }
+.info { color: #1565c0; }
+.debug, .trace { color: #757575; }
+</style>
+</head>
+<body>
+<table>
+<tr><th>TIME</th><th>LEVEL</th><th>RUN</th><th>MESSAGE</th></tr>
+"#;
+
+const HTML_FOOTER: &str = "</table>\n</body>\n</html>\n";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+
+    fn entry(level: u32, message: &str) -> LogEntry {
+        serde_json::from_value(serde_json::json!({
+            "level": level, "message": message, "timestamp": "2026-02-21T17:34:05.301Z",
+            "flow_run_id": "run-1"
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn format_from_extension() {
+        let f = |p: &str| LogFileFormat::from_path(Path::new(p));
+        assert_eq!(f("run.log"), LogFileFormat::Text);
+        assert_eq!(f("run.jsonl"), LogFileFormat::Jsonl);
+        assert_eq!(f("run.CSV"), LogFileFormat::Csv);
+        assert_eq!(f("run.html.gz"), LogFileFormat::Html);
+        assert_eq!(f("run"), LogFileFormat::Text);
+    }
+
+    #[test]
+    fn csv_quotes_fields_that_need_it() {
+        assert_eq!(csv_field("plain"), "plain");
+        assert_eq!(csv_field("a,b"), "\"a,b\"");
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+    }
+
+    #[test]
+    fn text_export_matches_terminal_lines_and_is_atomic() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("run.log");
+
+        let mut file = LogFile::create(
+            &path,
+            LogFileFormat::Text,
+            false,
+            false,
+            TimeDisplay::default(),
+        )
+        .unwrap();
+        file.write_batch(&[entry(20, "hello"), entry(40, "boom")])
+            .unwrap();
+        assert!(!path.exists(), "nothing at the path before finish");
+
+        assert_eq!(file.finish().unwrap(), 2);
+        let contents = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(
+            contents,
+            "2026-02-21T17:34:05 | INFO     | hello\n2026-02-21T17:34:05 | ERROR    | boom\n"
+        );
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn abandoned_export_removes_temporary_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("run.csv");
+
+        let mut file = LogFile::create(
+            &path,
+            LogFileFormat::Csv,
+            false,
+            false,
+            TimeDisplay::default(),
+        )
+        .unwrap();
+        file.write_batch(&[entry(20, "hello")]).unwrap();
+        drop(file);
+
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 0);
+    }
+
+    #[test]
+    fn streaming_export_is_readable_before_finish() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("run.jsonl");
+
+        let mut file = LogFile::create(
+            &path,
+            LogFileFormat::Jsonl,
+            false,
+            true,
+            TimeDisplay::default(),
+        )
+        .unwrap();
+        file.write_batch(&[entry(20, "hello")]).unwrap();
+        let partial = std::fs::read_to_string(&path).unwrap();
+        assert!(partial.contains(r#""message":"hello""#));
+
+        file.finish().unwrap();
+    }
+
+    #[test]
+    fn gzip_html_export_round_trips() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("run.html.gz");
+
+        let mut file = LogFile::create(
+            &path,
+            LogFileFormat::Html,
+            true,
+            false,
+            TimeDisplay::default(),
+        )
+        .unwrap();
+        file.write_batch(&[entry(40, "<b>boom</b>")]).unwrap();
+        file.finish().unwrap();
+
+        let mut html = String::new();
+        flate2::read::GzDecoder::new(File::open(&path).unwrap())
+            .read_to_string(&mut html)
+            .unwrap();
+        assert!(html.contains(r#"<tr class="error">"#));
+        assert!(html.contains("&lt;b&gt;boom&lt;/b&gt;"));
+        assert!(html.ends_with("</html>\n"));
+    }
+}
@@ -0,0 +1,223 @@
+use crate::error::{PfpError, Result};
+use crate::policy::Policy;
+use crate::settings::Protection;
+use std::io::{BufRead, Write};
+
+/// Confirmation flags shared by commands that change Prefect state.
+#[derive(Debug, Default, clap::Args)]
+pub struct ConfirmArgs {
+    /// Skip the confirmation prompt
+    #[arg(long)]
+    pub yes: bool,
+    /// Act on protected deployments or pools without typing their name
+    #[arg(long = "i-know")]
+    pub i_know: bool,
+}
+
+/// Decides whether a state-changing action may go ahead.
+///
+/// On a terminal the user is asked first unless `--yes` was given. Targets
+/// matching the `[protect]` settings need their name typed back, or
+/// `--i-know`; `--yes` alone is never enough for them, interactive or not.
+/// The policy file is checked before anyone is asked.
+#[derive(Debug, Default)]
+pub struct Guard {
+    confirm: ConfirmArgs,
+    protect: Protection,
+    interactive: bool,
+    policy: Policy,
+}
+
+impl Guard {
+    pub fn new(confirm: ConfirmArgs, protect: Protection, interactive: bool) -> Self {
+        Self {
+            confirm,
+            protect,
+            interactive,
+            policy: Policy::default(),
+        }
+    }
+
+    pub fn with_policy(mut self, policy: Policy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    pub fn policy(&self) -> &Policy {
+        &self.policy
+    }
+
+    /// Whether any protection rule is configured. Lets callers skip lookups
+    /// that are only needed to evaluate the rules.
+    pub fn protects_anything(&self) -> bool {
+        !self.protect.is_empty()
+    }
+
+    pub fn is_protected_deployment(&self, full_name: &str) -> bool {
+        self.protect.deployment(full_name)
+    }
+
+    pub fn is_protected_pool(&self, name: &str) -> bool {
+        self.protect.pool(name)
+    }
+
+    /// Confirm an action on one deployment.
+    pub fn confirm_deployment(&self, action: &str, full_name: &str) -> Result<()> {
+        let protected: Vec<String> = self
+            .is_protected_deployment(full_name)
+            .then(|| full_name.to_string())
+            .into_iter()
+            .collect();
+        self.confirm(&format!("{} {}", action, full_name), &protected)
+    }
+
+    /// Confirm an action on one work pool.
+    pub fn confirm_pool(&self, action: &str, name: &str) -> Result<()> {
+        let protected: Vec<String> = self
+            .is_protected_pool(name)
+            .then(|| name.to_string())
+            .into_iter()
+            .collect();
+        self.confirm(&format!("{} {}", action, name), &protected)
+    }
+
+    /// Confirm `action`. `protected` lists the protected deployments and
+    /// pools it touches. Non-interactive use goes ahead unless something is
+    /// protected.
+    pub fn confirm(&self, action: &str, protected: &[String]) -> Result<()> {
+        self.check(action, protected, false, &mut std::io::stdin().lock())
+    }
+
+    /// Like `confirm`, but non-interactive use also needs `--yes`. For
+    /// actions on many runs at once.
+    pub fn confirm_bulk(&self, action: &str, protected: &[String]) -> Result<()> {
+        self.check(action, protected, true, &mut std::io::stdin().lock())
+    }
+
+    fn check(
+        &self,
+        action: &str,
+        protected: &[String],
+        bulk: bool,
+        input: &mut dyn BufRead,
+    ) -> Result<()> {
+        if !protected.is_empty() {
+            if self.confirm.i_know {
+                return Ok(());
+            }
+            let token = match protected {
+                [name] => name.clone(),
+                _ => protected.len().to_string(),
+            };
+            if !self.interactive {
+                return Err(PfpError::Validation(format!(
+                    "{} touches protected {}; pass --i-know to proceed non-interactively",
+                    action,
+                    protected.join(", ")
+                )));
+            }
+            let answer = prompt(
+                input,
+                &format!(
+                    "{} touches protected {}.\nType '{}' to confirm: ",
+                    action,
+                    protected.join(", "),
+                    token
+                ),
+            )?;
+            return if answer == token {
+                Ok(())
+            } else {
+                Err(PfpError::Validation(
+                    "confirmation did not match, aborted".into(),
+                ))
+            };
+        }
+
+        if self.confirm.yes || self.confirm.i_know {
+            return Ok(());
+        }
+        if !self.interactive {
+            return if bulk {
+                Err(PfpError::Validation(format!(
+                    "{}: refusing without --yes",
+                    action
+                )))
+            } else {
+                Ok(())
+            };
+        }
+        let answer = prompt(input, &format!("{}? [y/N] ", action))?;
+        if matches!(answer.to_lowercase().as_str(), "y" | "yes") {
+            Ok(())
+        } else {
+            Err(PfpError::Validation("aborted".into()))
+        }
+    }
+}
+
+fn prompt(input: &mut dyn BufRead, question: &str) -> Result<String> {
+    eprint!("{}", question);
+    let _ = std::io::stderr().flush();
+    let mut answer = String::new();
+    input
+        .read_line(&mut answer)
+        .map_err(|e| PfpError::Io(format!("reading confirmation: {}", e)))?;
+    Ok(answer.trim().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn guard(yes: bool, i_know: bool, interactive: bool) -> Guard {
+        Guard::new(
+            ConfirmArgs { yes, i_know },
+            Protection {
+                deployments: vec!["*-prod".to_string()],
+                pools: vec![],
+            },
+            interactive,
+        )
+    }
+
+    fn check(guard: &Guard, protected: &[&str], bulk: bool, answer: &str) -> Result<()> {
+        let protected: Vec<String> = protected.iter().map(|s| s.to_string()).collect();
+        guard.check("Pause x", &protected, bulk, &mut answer.as_bytes())
+    }
+
+    #[test]
+    fn non_interactive_unprotected_goes_ahead() {
+        assert!(check(&guard(false, false, false), &[], false, "").is_ok());
+    }
+
+    #[test]
+    fn non_interactive_bulk_needs_yes() {
+        assert!(check(&guard(false, false, false), &[], true, "").is_err());
+        assert!(check(&guard(true, false, false), &[], true, "").is_ok());
+    }
+
+    #[test]
+    fn interactive_prompt_accepts_only_yes() {
+        assert!(check(&guard(false, false, true), &[], false, "y\n").is_ok());
+        assert!(check(&guard(false, false, true), &[], false, "YES\n").is_ok());
+        assert!(check(&guard(false, false, true), &[], false, "\n").is_err());
+    }
+
+    #[test]
+    fn protected_needs_i_know_or_typed_name() {
+        let protected = ["f/app-prod"];
+        let err = check(&guard(true, false, false), &protected, false, "").unwrap_err();
+        assert!(matches!(err, PfpError::Validation(ref msg) if msg.contains("--i-know")));
+        assert!(check(&guard(false, true, false), &protected, false, "").is_ok());
+        assert!(check(&guard(true, false, true), &protected, false, "f/app-prod\n").is_ok());
+        assert!(check(&guard(true, false, true), &protected, false, "y\n").is_err());
+    }
+
+    #[test]
+    fn several_protected_targets_need_their_count_typed() {
+        let protected = ["a-prod", "b-prod"];
+        assert!(check(&guard(false, false, true), &protected, true, "2\n").is_ok());
+        assert!(check(&guard(false, false, true), &protected, true, "a-prod\n").is_err());
+    }
+}
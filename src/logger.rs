@@ -1,11 +1,16 @@
 use chrono::Utc;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet};
 use std::fs::{self, OpenOptions};
-use std::io::{self, Write};
+use std::io::{self, BufRead, BufReader, Write};
 use std::path::{Path, PathBuf};
+use std::sync::Mutex;
 
 use crate::error::PfpError;
+use crate::redact::Redactor;
+use crate::settings::{LogSettings, LogSink, Settings};
+use crate::timespec;
 
 const DEFAULT_MAX_LOG_FILE_BYTES: u64 = 25 * 1024 * 1024;
 const MAX_ROTATED_LOG_FILES: usize = 10;
@@ -21,6 +26,48 @@ pub struct InvocationRecord {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub error: Option<String>,
     pub duration_ms: u64,
+    /// What the command resolved and did, see `record_result`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result: Option<Value>,
+}
+
+/// An invocation record read back from the log by `pfp history`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LoggedInvocation {
+    pub version: String,
+    pub ts: String,
+    pub command: String,
+    #[serde(default)]
+    pub args: Value,
+    pub outcome: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    pub duration_ms: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub result: Option<Value>,
+}
+
+impl LoggedInvocation {
+    pub fn is_ok(&self) -> bool {
+        self.outcome == "ok"
+    }
+}
+
+/// Result metadata gathered while the command runs.
+static RESULT: Mutex<BTreeMap<String, Value>> = Mutex::new(BTreeMap::new());
+
+/// Attach a fact about what this invocation did to its record, e.g. the
+/// resolved deployment or the flow run it created. A later value for the same
+/// key replaces the earlier one.
+pub fn record_result(key: &str, value: impl Into<Value>) {
+    if let Ok(mut result) = RESULT.lock() {
+        result.insert(key.to_string(), value.into());
+    }
+}
+
+fn take_result() -> Option<Value> {
+    let result = std::mem::take(&mut *RESULT.lock().ok()?);
+    (!result.is_empty()).then(|| result.into_iter().collect::<serde_json::Map<_, _>>().into())
 }
 
 fn default_log_dir() -> PathBuf {
@@ -45,16 +92,191 @@ pub fn make_entry(
         ts: Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Millis, true),
         command: command.to_string(),
         args,
-        outcome: if result.is_ok() { "ok" } else { "error" },
+        outcome: match result {
+            Ok(()) => "ok",
+            Err(PfpError::PolicyDenied(_)) => "denied",
+            Err(_) => "error",
+        },
         error: result.as_ref().err().map(|e| e.to_string()),
         duration_ms,
+        result: None,
     }
 }
 
-/// Log a CLI invocation. Errors are printed to stderr but never fail the process.
-pub fn log_invocation(command: &str, args: Value, result: &Result<(), PfpError>, duration_ms: u64) {
-    let entry = make_entry(command, args, result, duration_ms);
-    log_entry_to(&entry, &log_file_path());
+/// Log settings, or, when the settings file is unreadable, the default sinks
+/// with parameter values left out.
+fn log_settings() -> LogSettings {
+    Settings::load().map(|s| s.log).unwrap_or(LogSettings {
+        param_values: false,
+        ..Default::default()
+    })
+}
+
+/// Log a CLI invocation, with secret parameter values masked, to every
+/// configured sink. `secret_paths` are the parameter paths a deployment
+/// schema marked as secret. Errors are printed to stderr but never fail the
+/// process.
+pub async fn log_invocation(
+    command: &str,
+    args: Value,
+    result: &Result<(), PfpError>,
+    duration_ms: u64,
+    secret_paths: BTreeSet<String>,
+) {
+    let settings = log_settings();
+    let args = Redactor::new(&settings, secret_paths).redact_args(args);
+    let mut entry = make_entry(command, args, result, duration_ms);
+    entry.result = take_result();
+    for sink in &settings.sinks {
+        write_to_sink(sink, &entry).await;
+    }
+}
+
+/// Write one record to one sink, reporting rather than returning failures.
+async fn write_to_sink(sink: &LogSink, entry: &InvocationRecord) {
+    let outcome = match sink {
+        LogSink::File { path } => {
+            let path = path.clone().unwrap_or_else(log_file_path);
+            log_entry_to(entry, &path);
+            return;
+        }
+        LogSink::Syslog { socket } => serialize(entry).and_then(|json| {
+            send_syslog(socket, &json, entry.outcome == "ok").map_err(|e| e.to_string())
+        }),
+        LogSink::Unix { socket } => serialize(entry)
+            .and_then(|json| send_unix_stream(socket, &json).map_err(|e| e.to_string())),
+        LogSink::Http {
+            url,
+            headers,
+            timeout,
+        } => match serialize(entry) {
+            Ok(json) => post_http(url, headers, timeout, json).await,
+            Err(e) => Err(e),
+        },
+    };
+    if let Err(e) = outcome {
+        eprintln!("pfp: failed to write log entry to {}: {e}", sink_name(sink));
+    }
+}
+
+fn serialize(entry: &InvocationRecord) -> Result<String, String> {
+    serde_json::to_string(entry).map_err(|e| format!("cannot serialize entry: {e}"))
+}
+
+fn sink_name(sink: &LogSink) -> String {
+    match sink {
+        LogSink::File { path } => path
+            .clone()
+            .unwrap_or_else(log_file_path)
+            .display()
+            .to_string(),
+        LogSink::Syslog { socket } | LogSink::Unix { socket } => socket.display().to_string(),
+        LogSink::Http { url, .. } => url.clone(),
+    }
+}
+
+/// Send an RFC 3164 message with facility `user`, severity `info` for
+/// successful invocations and `err` otherwise.
+#[cfg(unix)]
+fn send_syslog(socket: &Path, json: &str, ok: bool) -> io::Result<()> {
+    const FACILITY_USER: u8 = 1;
+    let severity = if ok { 6 } else { 3 };
+    let message = format!(
+        "<{}>pfp[{}]: {}",
+        FACILITY_USER * 8 + severity,
+        std::process::id(),
+        json
+    );
+    let datagram = std::os::unix::net::UnixDatagram::unbound()?;
+    datagram.send_to(message.as_bytes(), socket)?;
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn send_syslog(_socket: &Path, _json: &str, _ok: bool) -> io::Result<()> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "syslog sinks need a Unix socket",
+    ))
+}
+
+#[cfg(unix)]
+fn send_unix_stream(socket: &Path, json: &str) -> io::Result<()> {
+    let mut stream = std::os::unix::net::UnixStream::connect(socket)?;
+    stream.set_write_timeout(Some(std::time::Duration::from_secs(2)))?;
+    stream.write_all(format!("{json}\n").as_bytes())
+}
+
+#[cfg(not(unix))]
+fn send_unix_stream(_socket: &Path, _json: &str) -> io::Result<()> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "unix sinks need a Unix socket",
+    ))
+}
+
+async fn post_http(
+    url: &str,
+    headers: &BTreeMap<String, String>,
+    timeout: &str,
+    json: String,
+) -> Result<(), String> {
+    let timeout = timespec::parse_duration(timeout)?
+        .to_std()
+        .map_err(|e| e.to_string())?;
+    let client = reqwest::Client::builder()
+        .timeout(timeout)
+        .build()
+        .map_err(|e| e.to_string())?;
+    let mut request = client
+        .post(url)
+        .header("content-type", "application/json")
+        .body(json);
+    for (name, value) in headers {
+        request = request.header(name, value);
+    }
+    let response = request.send().await.map_err(|e| e.to_string())?;
+    response.error_for_status().map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Every record in the local invocation log, oldest first, including the
+/// rotated `.N` files. Reads the first configured file sink, or the default
+/// `~/.pfp/pfp.jsonl`. Lines that cannot be parsed are skipped.
+pub fn read_history() -> Vec<LoggedInvocation> {
+    let path = log_settings()
+        .sinks
+        .into_iter()
+        .find_map(|sink| match sink {
+            LogSink::File { path } => path,
+            _ => None,
+        })
+        .unwrap_or_else(log_file_path);
+    read_history_from(&path, MAX_ROTATED_LOG_FILES)
+}
+
+fn read_history_from(path: &Path, keep_files: usize) -> Vec<LoggedInvocation> {
+    let rotated = (1..=keep_files)
+        .rev()
+        .map(|index| rotated_log_path(path, index));
+    let mut records = Vec::new();
+    for file_path in rotated.chain(std::iter::once(path.to_path_buf())) {
+        let file = match fs::File::open(&file_path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => continue,
+            Err(e) => {
+                eprintln!("pfp: failed to read {}: {e}", file_path.display());
+                continue;
+            }
+        };
+        records.extend(
+            BufReader::new(file)
+                .lines()
+                .map_while(|line| line.ok())
+                .filter_map(|line| serde_json::from_str(&line).ok()),
+        );
+    }
+    records
 }
 
 /// Write an invocation record to a specific path (for testing).
@@ -132,6 +354,27 @@ fn maybe_rotate_before_append(
     Ok(())
 }
 
+/// The lock file serializing rotation and appends across pfp processes. It
+/// sits next to the log rather than being the log itself, because rotation
+/// renames the log out from under anyone holding it.
+fn lock_path(path: &Path) -> PathBuf {
+    let mut name = path.as_os_str().to_os_string();
+    name.push(".lock");
+    PathBuf::from(name)
+}
+
+/// Take an exclusive advisory lock on the log's lock file. The lock is
+/// released when the returned file is dropped.
+fn lock_log(path: &Path) -> io::Result<fs::File> {
+    let lock = OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(lock_path(path))?;
+    lock.lock()?;
+    Ok(lock)
+}
+
 fn log_entry_to_with_rotation(
     entry: &InvocationRecord,
     path: &Path,
@@ -145,15 +388,25 @@ fn log_entry_to_with_rotation(
         }
     }
 
-    let json = match serde_json::to_string(entry) {
-        Ok(j) => j,
+    let line = match serde_json::to_string(entry) {
+        Ok(json) => json + "\n",
         Err(e) => {
             eprintln!("pfp: failed to serialize log entry: {e}");
             return;
         }
     };
 
-    if let Err(e) = maybe_rotate_before_append(path, json.len(), max_bytes, keep_files) {
+    // Without the lock the entry is still appended, just without protection
+    // against a concurrent rotation.
+    let _lock = match lock_log(path) {
+        Ok(lock) => Some(lock),
+        Err(e) => {
+            eprintln!("pfp: failed to lock log file: {e}");
+            None
+        }
+    };
+
+    if let Err(e) = maybe_rotate_before_append(path, line.len() - 1, max_bytes, keep_files) {
         eprintln!("pfp: failed to rotate log files: {e}");
     }
 
@@ -165,7 +418,9 @@ fn log_entry_to_with_rotation(
         }
     };
 
-    if let Err(e) = writeln!(file, "{json}") {
+    // One write of the whole line, so an O_APPEND writer that is not holding
+    // the lock cannot interleave with it.
+    if let Err(e) = file.write_all(line.as_bytes()) {
         eprintln!("pfp: failed to write log entry: {e}");
     }
 }
@@ -198,6 +453,7 @@ mod tests {
                 None
             },
             duration_ms: 42,
+            result: None,
         }
     }
 
@@ -283,6 +539,51 @@ mod tests {
         let _ = fs::remove_dir_all(&dir);
     }
 
+    #[test]
+    fn test_read_history_spans_rotated_files_oldest_first() {
+        let dir = unique_test_dir("read-history");
+        let path = dir.join("test.jsonl");
+
+        for i in 1..=3 {
+            let entry = sample_entry(&format!("cmd-{i}"), "ok");
+            log_entry_to_with_rotation(&entry, &path, 1, 10);
+        }
+        fs::write(
+            rotated_log_path(&path, 1),
+            format!(
+                "not json\n{}\n",
+                fs::read_to_string(rotated_log_path(&path, 1)).unwrap()
+            ),
+        )
+        .unwrap();
+
+        let commands: Vec<String> = read_history_from(&path, 10)
+            .into_iter()
+            .map(|record| record.command)
+            .collect();
+        assert_eq!(commands, vec!["cmd-1", "cmd-2", "cmd-3"]);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_recorded_result_is_taken_once() {
+        // Other tests may record results concurrently, so only look at keys
+        // nothing else uses.
+        record_result("test_run", "run-1");
+        record_result("test_state", "Running");
+        record_result("test_state", "Completed");
+        let taken = take_result().unwrap();
+        assert_eq!(taken["test_run"], "run-1");
+        assert_eq!(taken["test_state"], "Completed");
+        assert!(take_result().is_none_or(|rest| rest.get("test_run").is_none()));
+
+        let mut entry = sample_entry("run", "ok");
+        entry.result = Some(serde_json::json!({"flow_run_id": "run-1"}));
+        let json = serde_json::to_string(&entry).unwrap();
+        assert!(json.contains(r#""result":{"flow_run_id":"run-1"}"#));
+    }
+
     #[test]
     fn test_make_entry_ok_result() {
         let result: Result<(), PfpError> = Ok(());
@@ -307,6 +608,19 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_make_entry_policy_denial() {
+        let result: Result<(), PfpError> = Err(PfpError::PolicyDenied(
+            "running etl/nightly is not allowed".into(),
+        ));
+        let entry = make_entry("run", serde_json::json!({"query": "nightly"}), &result, 7);
+        assert_eq!(entry.outcome, "denied");
+        assert_eq!(
+            entry.error.as_deref(),
+            Some("Denied by policy: running etl/nightly is not allowed")
+        );
+    }
+
     #[test]
     fn test_configured_max_bytes_default() {
         // With no env var set, should return the default.
@@ -321,6 +635,138 @@ mod tests {
         std::env::remove_var(LOG_MAX_BYTES_ENV);
     }
 
+    #[test]
+    fn test_concurrent_writers_keep_every_line_whole() {
+        const THREADS: usize = 16;
+        const PER_THREAD: usize = 50;
+        let dir = unique_test_dir("concurrent");
+        let path = dir.join("test.jsonl");
+
+        std::thread::scope(|scope| {
+            for thread in 0..THREADS {
+                let path = &path;
+                scope.spawn(move || {
+                    for i in 0..PER_THREAD {
+                        let mut entry = sample_entry(&format!("t{thread}-{i}"), "ok");
+                        entry.args = serde_json::json!({"padding": "x".repeat(2_000)});
+                        log_entry_to_with_rotation(&entry, path, 64 * 1024, 1_000);
+                    }
+                });
+            }
+        });
+
+        let records = read_history_from(&path, 1_000);
+        let mut commands: Vec<String> = records.into_iter().map(|r| r.command).collect();
+        commands.sort();
+        commands.dedup();
+        assert_eq!(commands.len(), THREADS * PER_THREAD);
+
+        for index in 0..=1_000 {
+            let file = if index == 0 {
+                path.clone()
+            } else {
+                rotated_log_path(&path, index)
+            };
+            let Ok(content) = fs::read_to_string(&file) else {
+                continue;
+            };
+            assert!(content.len() as u64 <= 64 * 1024);
+            for line in content.lines() {
+                serde_json::from_str::<LoggedInvocation>(line).unwrap();
+            }
+        }
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn test_file_sink_with_custom_path() {
+        let dir = unique_test_dir("file-sink");
+        let path = dir.join("audit.jsonl");
+        let sink = LogSink::File {
+            path: Some(path.clone()),
+        };
+        write_to_sink(&sink, &sample_entry("ls", "ok")).await;
+        let content = fs::read_to_string(&path).unwrap();
+        assert!(content.contains("\"command\":\"ls\""));
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_unix_sink_writes_one_json_line() {
+        use std::io::Read;
+        let dir = unique_test_dir("unix-sink");
+        fs::create_dir_all(&dir).unwrap();
+        let socket = dir.join("pfp.sock");
+        let listener = std::os::unix::net::UnixListener::bind(&socket).unwrap();
+
+        let sink = LogSink::Unix {
+            socket: socket.clone(),
+        };
+        write_to_sink(&sink, &sample_entry("run", "ok")).await;
+
+        let (mut stream, _) = listener.accept().unwrap();
+        let mut received = String::new();
+        stream.read_to_string(&mut received).unwrap();
+        assert!(received.ends_with('\n'));
+        let record: LoggedInvocation = serde_json::from_str(received.trim_end()).unwrap();
+        assert_eq!(record.command, "run");
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_syslog_sink_sends_prioritized_datagram() {
+        let dir = unique_test_dir("syslog-sink");
+        fs::create_dir_all(&dir).unwrap();
+        let socket = dir.join("log");
+        let server = std::os::unix::net::UnixDatagram::bind(&socket).unwrap();
+
+        let sink = LogSink::Syslog {
+            socket: socket.clone(),
+        };
+        write_to_sink(&sink, &sample_entry("cancel", "error")).await;
+
+        let mut buf = [0u8; 4096];
+        let n = server.recv(&mut buf).unwrap();
+        let message = std::str::from_utf8(&buf[..n]).unwrap();
+        assert!(message.starts_with(&format!("<11>pfp[{}]: {{", std::process::id())));
+        assert!(message.contains("\"command\":\"cancel\""));
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn test_http_sink_posts_record_with_headers() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/ingest")
+            .match_header("content-type", "application/json")
+            .match_header("authorization", "Bearer abc")
+            .match_body(mockito::Matcher::PartialJsonString(
+                r#"{"command": "ls", "outcome": "ok"}"#.to_string(),
+            ))
+            .with_status(204)
+            .create_async()
+            .await;
+
+        let sink = LogSink::Http {
+            url: format!("{}/ingest", server.url()),
+            headers: BTreeMap::from([("authorization".to_string(), "Bearer abc".to_string())]),
+            timeout: "2s".to_string(),
+        };
+        write_to_sink(&sink, &sample_entry("ls", "ok")).await;
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_unreachable_sink_does_not_panic() {
+        let sink = LogSink::Unix {
+            socket: unique_test_dir("missing").join("nope.sock"),
+        };
+        write_to_sink(&sink, &sample_entry("ls", "ok")).await;
+    }
+
     #[test]
     fn test_configured_max_bytes_zero_uses_default() {
         std::env::set_var(LOG_MAX_BYTES_ENV, "0");
@@ -2,18 +2,37 @@ mod client;
 mod commands;
 mod config;
 mod error;
+mod export;
+mod guard;
 mod logger;
 mod models;
+mod notify;
 mod output;
 mod params;
+mod policy;
+mod redact;
 mod resolve;
+mod settings;
+mod timefmt;
+mod timespec;
 mod validate;
 
 use clap::{Parser, Subcommand};
 use client::PrefectClient;
+use commands::cancel::{BulkCancelArgs, CancelModeArgs};
+use commands::history::HistoryFilterArgs;
+use commands::logs::{LogExportArgs, LogFilterArgs};
+use commands::runs::RunFilterArgs;
 use config::Config;
 use error::Result;
+use guard::{ConfirmArgs, Guard};
+use output::OutputFormat;
+use policy::Policy;
+use settings::Settings;
+use std::collections::BTreeSet;
+use std::io::IsTerminal;
 use std::time::Instant;
+use timefmt::TimeDisplay;
 
 #[derive(Parser)]
 #[command(name = "pfp", version, about = "Prefect CLI")]
@@ -22,6 +41,14 @@ struct Cli {
     #[arg(long, global = true)]
     server: Option<String>,
 
+    /// Timezone for displayed times: local, utc, or an IANA name (e.g. Europe/Berlin)
+    #[arg(long, global = true, default_value = "local", value_parser = timefmt::Zone::parse)]
+    tz: timefmt::Zone,
+
+    /// Show dates in tables instead of relative times like "3m ago"
+    #[arg(long, global = true)]
+    absolute: bool,
+
     #[command(subcommand)]
     command: Commands,
 }
@@ -39,24 +66,62 @@ enum Commands {
         query: String,
         #[arg(long)]
         watch: bool,
+        /// Fire the notifiers from ~/.pfp/config.toml when the watched run
+        /// ends (a desktop notification if none are configured)
+        #[arg(long, requires = "watch")]
+        notify: bool,
         #[arg(long = "set", num_args = 1)]
         sets: Vec<String>,
+        /// Create one run per value, e.g. environment=dev,staging,prod
+        /// (repeatable; several keys run every combination)
+        #[arg(long = "matrix", num_args = 1)]
+        matrix: Vec<String>,
         /// Additional flow-run tag (repeatable; "manual" is always included)
         #[arg(long = "tag", num_args = 1)]
         tags: Vec<String>,
         /// Read flow-run parameters as JSON from a file, or "-" for stdin.
-        /// Merged under any --set overrides (--set wins).
+        /// Merged under any --set overrides (--set wins). A JSON array of
+        /// objects creates one run per object.
         #[arg(long = "params-file")]
         params_file: Option<String>,
         #[arg(long)]
         json: bool,
+        /// Print one compact JSON object per line (NDJSON)
+        #[arg(long, conflicts_with = "json")]
+        jsonl: bool,
+        #[command(flatten)]
+        confirm: ConfirmArgs,
+    },
+    /// Run the steps of a pipeline file in order, watching each run
+    PlanRun {
+        /// Pipeline file with [[step]] entries
+        path: String,
+        /// Fire the notifiers from ~/.pfp/config.toml as each step's run ends
+        #[arg(long)]
+        notify: bool,
+        /// Print only the final per-step report, as JSON
+        #[arg(long)]
+        json: bool,
+        #[command(flatten)]
+        confirm: ConfirmArgs,
     },
-    /// Show recent flow runs for a deployment
+    /// Show recent flow runs for a deployment, or for all deployments
     Runs {
-        /// Deployment name (substring match)
-        query: String,
+        /// Deployment name (substring match); omit to list runs of every deployment
+        query: Option<String>,
+        #[command(flatten)]
+        filter: RunFilterArgs,
+        /// Maximum number of flow runs to show
+        #[arg(long, default_value_t = 10)]
+        limit: usize,
+        /// Add a QUEUED column: time from expected start to actual start
+        #[arg(long)]
+        queued: bool,
         #[arg(long)]
         json: bool,
+        /// Print one compact JSON object per line (NDJSON)
+        #[arg(long, conflicts_with = "json")]
+        jsonl: bool,
     },
     /// Inspect one flow run by its full UUID
     Inspect {
@@ -65,38 +130,103 @@ enum Commands {
         #[arg(long)]
         json: bool,
     },
-    /// Show logs for a flow run
-    Logs {
+    /// List the task runs of a flow run
+    Tasks {
         /// Flow run ID or UUID prefix
         flow_run_id: String,
+        #[arg(long)]
+        json: bool,
+    },
+    /// Show logs for one or more flow runs, or a deployment's recent runs
+    Logs {
+        /// Flow run IDs or UUID prefixes; several runs are merged by timestamp
+        #[arg(required_unless_present = "deployment")]
+        flow_run_ids: Vec<String>,
+        #[command(flatten)]
+        filter: LogFilterArgs,
+        #[command(flatten)]
+        export: LogExportArgs,
         /// Maximum number of log entries to fetch
         #[arg(long)]
         limit: Option<usize>,
         /// Follow log output (like tail -f)
-        #[arg(long, short = 'f', visible_alias = "tail")]
+        #[arg(long, short = 'f')]
         follow: bool,
         #[arg(long)]
         json: bool,
+        /// Print one compact JSON object per line (NDJSON)
+        #[arg(long, conflicts_with = "json")]
+        jsonl: bool,
     },
     /// Pause a deployment
     Pause {
         /// Deployment name (substring match)
         query: String,
+        #[command(flatten)]
+        confirm: ConfirmArgs,
     },
     /// Resume a deployment
     Resume {
         /// Deployment name (substring match)
         query: String,
+        #[command(flatten)]
+        confirm: ConfirmArgs,
     },
     /// Activate every schedule attached to a deployment
     ScheduleResume {
         /// Deployment name (substring match)
         query: String,
+        #[command(flatten)]
+        confirm: ConfirmArgs,
     },
-    /// Cancel a running flow run
+    /// Cancel one flow run, or every run matching filters
     Cancel {
-        /// Flow run ID or UUID prefix
-        flow_run_id: String,
+        /// Flow run ID or UUID prefix; omit to select runs with filters
+        #[arg(conflicts_with_all = ["deployment", "states", "since", "until", "tags", "work_pool"])]
+        flow_run_id: Option<String>,
+        #[command(flatten)]
+        mode: CancelModeArgs,
+        #[command(flatten)]
+        bulk: BulkCancelArgs,
+        #[command(flatten)]
+        confirm: ConfirmArgs,
+    },
+    /// Wait for flow runs to finish
+    Wait {
+        /// Full flow run IDs; when none are given they are read from stdin,
+        /// as plain IDs or the output of `pfp run --json`
+        ids: Vec<String>,
+        /// Wait for every run to finish (the default)
+        #[arg(long)]
+        all: bool,
+        /// Return as soon as any run finishes
+        #[arg(long, conflicts_with = "all")]
+        any: bool,
+        /// Give up after this long (e.g. 90s, 30m); exits 2
+        #[arg(long)]
+        timeout: Option<String>,
+        /// Print the runs' final states as JSON
+        #[arg(long)]
+        json: bool,
+        /// Fire the notifiers from ~/.pfp/config.toml as each run finishes
+        #[arg(long)]
+        notify: bool,
+    },
+    /// Show past pfp invocations from the local log
+    History {
+        #[command(flatten)]
+        filter: HistoryFilterArgs,
+        /// Maximum number of invocations to show, newest first
+        #[arg(long, default_value_t = 20)]
+        limit: usize,
+        /// Per-command counts, errors and p50/p95 durations instead of a list
+        #[arg(long)]
+        summary: bool,
+        #[arg(long)]
+        json: bool,
+        /// Print one compact JSON object per line (NDJSON)
+        #[arg(long, conflicts_with = "json")]
+        jsonl: bool,
     },
     /// Inspect or change an exact work pool
     Pool {
@@ -107,6 +237,11 @@ enum Commands {
 
 #[derive(Subcommand)]
 enum PoolAction {
+    /// List every work pool with its nonterminal flow run count
+    Ls {
+        #[arg(long)]
+        json: bool,
+    },
     /// Show one exact work pool
     Status {
         /// Exact work pool name
@@ -125,6 +260,8 @@ enum PoolAction {
     Pause {
         /// Exact work pool name
         name: String,
+        #[command(flatten)]
+        confirm: ConfirmArgs,
     },
     /// Resume one exact work pool
     Resume {
@@ -147,12 +284,22 @@ async fn main() {
         _ => None,
     };
 
-    let (cmd_name, cmd_args) = describe_command(&cli.command, params_payload.as_ref());
+    let (cmd_name, mut cmd_args) = describe_command(&cli.command, params_payload.as_ref());
+    // Global display flags, when they change the default output.
+    if let Some(args) = cmd_args.as_object_mut() {
+        if cli.tz != timefmt::Zone::Local {
+            args.insert("tz".into(), cli.tz.to_string().into());
+        }
+        if cli.absolute {
+            args.insert("absolute".into(), true.into());
+        }
+    }
     let start = Instant::now();
-    let result = run(cli, params_payload).await;
+    let mut secret_paths = BTreeSet::new();
+    let result = run(cli, params_payload, &mut secret_paths).await;
     let duration_ms = start.elapsed().as_millis() as u64;
 
-    logger::log_invocation(&cmd_name, cmd_args, &result, duration_ms);
+    logger::log_invocation(&cmd_name, cmd_args, &result, duration_ms, secret_paths).await;
 
     if let Err(e) = result {
         eprintln!("Error: {}", e);
@@ -160,9 +307,8 @@ async fn main() {
     }
 }
 
-/// Extract subcommand name and args for logging.
-/// Note: --set values and --params-file payloads are logged in plaintext
-/// (same exposure as shell history).
+/// Extract subcommand name and args for logging. --set values and
+/// --params-file payloads are masked by `redact` before they are written.
 fn describe_command(
     cmd: &Commands,
     params_payload: Option<&Result<serde_json::Value>>,
@@ -172,10 +318,14 @@ fn describe_command(
         Commands::Run {
             query,
             watch,
+            notify,
             sets,
+            matrix,
             tags,
             params_file,
             json,
+            jsonl,
+            confirm,
         } => {
             let params_log = params_file.as_ref().map(|p| match params_payload {
                 Some(Ok(v)) => serde_json::json!({ "path": p, "payload": v }),
@@ -186,41 +336,178 @@ fn describe_command(
                 serde_json::json!({
                     "query": query,
                     "watch": watch,
+                    "notify": notify,
                     "sets": sets,
+                    "matrix": matrix,
                     "tags": tags,
                     "params_file": params_log,
                     "json": json,
+                    "jsonl": jsonl,
+                    "yes": confirm.yes,
+                    "i_know": confirm.i_know,
                 }),
             )
         }
-        Commands::Runs { query, json } => (
+        Commands::PlanRun {
+            path,
+            notify,
+            json,
+            confirm,
+        } => (
+            "plan-run".into(),
+            serde_json::json!({
+                "path": path,
+                "notify": notify,
+                "json": json,
+                "yes": confirm.yes,
+                "i_know": confirm.i_know,
+            }),
+        ),
+        Commands::Runs {
+            query,
+            filter,
+            limit,
+            queued,
+            json,
+            jsonl,
+        } => (
             "runs".into(),
-            serde_json::json!({ "query": query, "json": json }),
+            serde_json::json!({
+                "query": query,
+                "states": filter.states,
+                "since": filter.since,
+                "until": filter.until,
+                "tags": filter.tags,
+                "work_pool": filter.work_pool,
+                "limit": limit,
+                "queued": queued,
+                "json": json,
+                "jsonl": jsonl,
+            }),
         ),
         Commands::Inspect { flow_run_id, json } => (
             "inspect".into(),
             serde_json::json!({ "flow_run_id": flow_run_id, "json": json }),
         ),
+        Commands::Tasks { flow_run_id, json } => (
+            "tasks".into(),
+            serde_json::json!({ "flow_run_id": flow_run_id, "json": json }),
+        ),
         Commands::Logs {
-            flow_run_id,
+            flow_run_ids,
+            filter,
+            export,
             limit,
             follow,
             json,
+            jsonl,
         } => (
             "logs".into(),
-            serde_json::json!({ "flow_run_id": flow_run_id, "limit": limit, "follow": follow, "json": json }),
+            serde_json::json!({
+                "flow_run_ids": flow_run_ids,
+                "deployment": filter.deployment,
+                "task": filter.task,
+                "level": filter.level,
+                "since": filter.since,
+                "until": filter.until,
+                "grep": filter.grep,
+                "tail": filter.tail.flatten(),
+                "output": export.output,
+                "format": export.file_format.map(|f| format!("{:?}", f).to_lowercase()),
+                "gzip": export.gzip,
+                "limit": limit,
+                "follow": *follow || filter.legacy_follow(),
+                "json": json,
+                "jsonl": jsonl,
+            }),
         ),
-        Commands::Pause { query } => ("pause".into(), serde_json::json!({ "query": query })),
-        Commands::Resume { query } => ("resume".into(), serde_json::json!({ "query": query })),
-        Commands::ScheduleResume { query } => (
+        Commands::Pause { query, confirm } => (
+            "pause".into(),
+            serde_json::json!({
+                "query": query,
+                "yes": confirm.yes,
+                "i_know": confirm.i_know,
+            }),
+        ),
+        Commands::Resume { query, confirm } => (
+            "resume".into(),
+            serde_json::json!({
+                "query": query,
+                "yes": confirm.yes,
+                "i_know": confirm.i_know,
+            }),
+        ),
+        Commands::ScheduleResume { query, confirm } => (
             "schedule-resume".into(),
-            serde_json::json!({ "query": query }),
+            serde_json::json!({
+                "query": query,
+                "yes": confirm.yes,
+                "i_know": confirm.i_know,
+            }),
         ),
-        Commands::Cancel { flow_run_id } => (
+        Commands::Cancel {
+            flow_run_id,
+            mode,
+            bulk,
+            confirm,
+        } => (
             "cancel".into(),
-            serde_json::json!({ "flow_run_id": flow_run_id }),
+            serde_json::json!({
+                "flow_run_id": flow_run_id,
+                "force": mode.force,
+                "wait": mode.wait,
+                "timeout": mode.wait.then_some(&mode.timeout),
+                "deployment": bulk.deployment,
+                "states": bulk.filter.states,
+                "since": bulk.filter.since,
+                "until": bulk.filter.until,
+                "tags": bulk.filter.tags,
+                "work_pool": bulk.filter.work_pool,
+                "limit": bulk.limit,
+                "concurrency": bulk.concurrency,
+                "yes": confirm.yes,
+                "i_know": confirm.i_know,
+            }),
+        ),
+        Commands::Wait {
+            ids,
+            all,
+            any,
+            timeout,
+            json,
+            notify,
+        } => (
+            "wait".into(),
+            serde_json::json!({
+                "ids": ids,
+                "all": all,
+                "any": any,
+                "timeout": timeout,
+                "json": json,
+                "notify": notify,
+            }),
+        ),
+        Commands::History {
+            filter,
+            limit,
+            summary,
+            json,
+            jsonl,
+        } => (
+            "history".into(),
+            serde_json::json!({
+                "command": filter.command,
+                "since": filter.since,
+                "errors": filter.errors,
+                "query": filter.query,
+                "limit": limit,
+                "summary": summary,
+                "json": json,
+                "jsonl": jsonl,
+            }),
         ),
         Commands::Pool { action } => match action {
+            PoolAction::Ls { json } => ("pool ls".into(), serde_json::json!({ "json": json })),
             PoolAction::Status { name, json } => (
                 "pool status".into(),
                 serde_json::json!({ "name": name, "json": json }),
@@ -229,9 +516,14 @@ fn describe_command(
                 "pool assert-idle".into(),
                 serde_json::json!({ "name": name, "json": json }),
             ),
-            PoolAction::Pause { name } => {
-                ("pool pause".into(), serde_json::json!({ "name": name }))
-            }
+            PoolAction::Pause { name, confirm } => (
+                "pool pause".into(),
+                serde_json::json!({
+                    "name": name,
+                    "yes": confirm.yes,
+                    "i_know": confirm.i_know,
+                }),
+            ),
             PoolAction::Resume { name } => {
                 ("pool resume".into(), serde_json::json!({ "name": name }))
             }
@@ -239,8 +531,20 @@ fn describe_command(
     }
 }
 
-async fn run(cli: Cli, params_payload: Option<Result<serde_json::Value>>) -> Result<()> {
-    let Cli { server, command } = cli;
+/// Dispatch the command. `secret_paths` collects parameter paths that
+/// deployment schemas mark as secret, for the invocation log.
+async fn run(
+    cli: Cli,
+    params_payload: Option<Result<serde_json::Value>>,
+    secret_paths: &mut BTreeSet<String>,
+) -> Result<()> {
+    let Cli {
+        server,
+        tz,
+        absolute,
+        command,
+    } = cli;
+    let time = TimeDisplay { zone: tz, absolute };
     match command {
         Commands::Ls { json } => {
             let config = Config::load(server.as_deref())?;
@@ -250,76 +554,214 @@ async fn run(cli: Cli, params_payload: Option<Result<serde_json::Value>>) -> Res
         Commands::Run {
             query,
             watch,
+            notify,
             sets,
+            matrix,
             tags,
             json,
+            jsonl,
+            confirm,
             ..
         } => {
             // Surface a bad --params-file before any config/network work.
             let params_base = params_payload.transpose()?;
             let config = Config::load(server.as_deref())?;
             let client = PrefectClient::new(config);
-            commands::run::run(client, query, watch, sets, tags, params_base, json).await
+            let format = OutputFormat::from_flags(json, jsonl);
+            let guard = load_guard(confirm)?;
+            let notifiers = if notify {
+                Settings::load()?.notifiers()
+            } else {
+                Vec::new()
+            };
+            let fan_out = !matrix.is_empty() || params_base.as_ref().is_some_and(|p| p.is_array());
+            if fan_out {
+                let payloads = match params_base {
+                    Some(serde_json::Value::Array(payloads)) => payloads,
+                    Some(payload) => vec![payload],
+                    None => Vec::new(),
+                };
+                return commands::run::run_many(
+                    client,
+                    query,
+                    watch,
+                    payloads,
+                    matrix,
+                    sets,
+                    tags,
+                    format,
+                    &guard,
+                    &notifiers,
+                    secret_paths,
+                )
+                .await;
+            }
+            commands::run::run(
+                client,
+                query,
+                watch,
+                sets,
+                tags,
+                params_base,
+                format,
+                time,
+                &guard,
+                &notifiers,
+                secret_paths,
+            )
+            .await
         }
-        Commands::Runs { query, json } => {
+        Commands::PlanRun {
+            path,
+            notify,
+            json,
+            confirm,
+        } => {
+            let pipeline = commands::plan_run::Pipeline::load(&path)?;
             let config = Config::load(server.as_deref())?;
             let client = PrefectClient::new(config);
-            commands::runs::run(client, query, json).await
+            let guard = load_guard(confirm)?;
+            let notifiers = if notify {
+                Settings::load()?.notifiers()
+            } else {
+                Vec::new()
+            };
+            commands::plan_run::run(client, pipeline, json, time, &guard, &notifiers).await
+        }
+        Commands::Runs {
+            query,
+            filter,
+            limit,
+            queued,
+            json,
+            jsonl,
+        } => {
+            let config = Config::load(server.as_deref())?;
+            let client = PrefectClient::new(config);
+            let format = OutputFormat::from_flags(json, jsonl);
+            commands::runs::run(client, query, filter, limit, queued, format, time).await
         }
         Commands::Inspect { flow_run_id, json } => {
             let config = Config::load(server.as_deref())?;
             let client = PrefectClient::new(config);
-            commands::inspect::run(client, flow_run_id, json).await
+            commands::inspect::run(client, flow_run_id, json, time).await
+        }
+        Commands::Tasks { flow_run_id, json } => {
+            let config = Config::load(server.as_deref())?;
+            let client = PrefectClient::new(config);
+            commands::tasks::run(client, flow_run_id, json, time).await
         }
         Commands::Logs {
-            flow_run_id,
+            flow_run_ids,
+            filter,
+            export,
             limit,
             follow,
             json,
+            jsonl,
         } => {
             let config = Config::load(server.as_deref())?;
             let client = PrefectClient::new(config);
-            commands::logs::run(client, flow_run_id, limit, follow, json).await
+            let format = OutputFormat::from_flags(json, jsonl);
+            commands::logs::run(
+                client,
+                flow_run_ids,
+                filter,
+                export,
+                limit,
+                follow,
+                format,
+                time,
+            )
+            .await
         }
-        Commands::Pause { query } => {
+        Commands::Pause { query, confirm } => {
             let config = Config::load(server.as_deref())?;
             let client = PrefectClient::new(config);
-            commands::pause::run(client, query).await
+            commands::pause::run(client, query, &load_guard(confirm)?).await
         }
-        Commands::Resume { query } => {
+        Commands::Resume { query, confirm } => {
             let config = Config::load(server.as_deref())?;
             let client = PrefectClient::new(config);
-            commands::resume::run(client, query).await
+            commands::resume::run(client, query, &load_guard(confirm)?).await
         }
-        Commands::ScheduleResume { query } => {
+        Commands::ScheduleResume { query, confirm } => {
             let config = Config::load(server.as_deref())?;
             let client = PrefectClient::new(config);
-            commands::schedule_resume::run(client, query).await
+            commands::schedule_resume::run(client, query, &load_guard(confirm)?).await
         }
-        Commands::Cancel { flow_run_id } => {
+        Commands::Cancel {
+            flow_run_id,
+            mode,
+            bulk,
+            confirm,
+        } => {
             let config = Config::load(server.as_deref())?;
             let client = PrefectClient::new(config);
-            commands::cancel::run(client, flow_run_id).await
+            commands::cancel::run(client, flow_run_id, mode, bulk, &load_guard(confirm)?, time)
+                .await
         }
+        Commands::Wait {
+            ids,
+            any,
+            timeout,
+            json,
+            notify,
+            ..
+        } => {
+            let config = Config::load(server.as_deref())?;
+            let client = PrefectClient::new(config);
+            let notifiers = if notify {
+                Settings::load()?.notifiers()
+            } else {
+                Vec::new()
+            };
+            commands::wait::run(client, ids, any, timeout, json, &notifiers).await
+        }
+        Commands::History {
+            filter,
+            limit,
+            summary,
+            json,
+            jsonl,
+        } => commands::history::run(
+            filter,
+            limit,
+            summary,
+            OutputFormat::from_flags(json, jsonl),
+            time,
+        ),
         Commands::Pool { action } => {
             let config = Config::load(server.as_deref())?;
             let client = PrefectClient::new(config);
             match action {
+                PoolAction::Ls { json } => commands::pool::list(client, json).await,
                 PoolAction::Status { name, json } => {
                     commands::pool::status(client, name, json).await
                 }
                 PoolAction::AssertIdle { name, json } => {
                     commands::pool::assert_idle(client, name, json).await
                 }
-                PoolAction::Pause { name } => commands::pool::set_paused(client, name, true).await,
+                PoolAction::Pause { name, confirm } => {
+                    commands::pool::set_paused(client, name, true, &load_guard(confirm)?).await
+                }
                 PoolAction::Resume { name } => {
-                    commands::pool::set_paused(client, name, false).await
+                    let guard = load_guard(ConfirmArgs::default())?;
+                    commands::pool::set_paused(client, name, false, &guard).await
                 }
             }
         }
     }
 }
 
+/// Checks for a state-changing command: the command's flags, the `[protect]`
+/// rules from pfp's settings file and the policy file.
+fn load_guard(confirm: ConfirmArgs) -> Result<Guard> {
+    let settings = Settings::load()?;
+    let guard = Guard::new(confirm, settings.protect, std::io::stdin().is_terminal());
+    Ok(guard.with_policy(Policy::load()?))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -348,6 +790,13 @@ mod tests {
         }
     }
 
+    #[test]
+    fn notify_requires_watch() {
+        assert!(Cli::try_parse_from(["pfp", "run", "etl", "--notify"]).is_err());
+        let cli = Cli::try_parse_from(["pfp", "run", "etl", "--watch", "--notify"]).unwrap();
+        assert!(matches!(cli.command, Commands::Run { notify: true, .. }));
+    }
+
     #[test]
     fn parses_pool_assert_idle_json() {
         let cli =
@@ -364,6 +813,42 @@ mod tests {
         }
     }
 
+    #[test]
+    fn parses_runs_filters_without_query() {
+        let cli = Cli::try_parse_from([
+            "pfp",
+            "runs",
+            "--state",
+            "failed,crashed",
+            "--since",
+            "24h",
+            "--tag",
+            "nightly",
+            "--work-pool",
+            "docker-prod",
+            "--limit",
+            "500",
+        ])
+        .unwrap();
+
+        match cli.command {
+            Commands::Runs {
+                query,
+                filter,
+                limit,
+                ..
+            } => {
+                assert!(query.is_none());
+                assert_eq!(filter.states, vec!["failed", "crashed"]);
+                assert_eq!(filter.since.as_deref(), Some("24h"));
+                assert_eq!(filter.tags, vec!["nightly"]);
+                assert_eq!(filter.work_pool.as_deref(), Some("docker-prod"));
+                assert_eq!(limit, 500);
+            }
+            _ => panic!("expected runs command"),
+        }
+    }
+
     #[test]
     fn parses_repeated_run_tags() {
         let cli = Cli::try_parse_from([
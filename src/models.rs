@@ -1,3 +1,5 @@
+use crate::timefmt::TimeDisplay;
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -68,6 +70,8 @@ pub struct FlowRun {
     #[serde(default)]
     pub deployment_id: Option<String>,
     #[serde(default)]
+    pub expected_start_time: Option<String>,
+    #[serde(default)]
     pub start_time: Option<String>,
     #[serde(default)]
     pub end_time: Option<String>,
@@ -77,56 +81,266 @@ pub struct FlowRun {
     pub estimated_run_time: f64,
     #[serde(default)]
     pub parameters: serde_json::Value,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub state: Option<StateDetail>,
 }
 
 impl FlowRun {
     pub fn is_terminal(&self) -> bool {
-        matches!(
-            self.state_type.as_str(),
-            "COMPLETED" | "FAILED" | "CANCELLED" | "CRASHED"
-        )
+        is_terminal_state(&self.state_type)
     }
 
     pub fn is_success(&self) -> bool {
         self.state_type == "COMPLETED"
     }
 
+    pub fn state_message(&self) -> Option<&str> {
+        self.state
+            .as_ref()
+            .and_then(|s| s.message.as_deref())
+            .filter(|m| !m.is_empty())
+    }
+
+    /// Run time in seconds as of `now`; see `run_seconds`.
+    pub fn duration_at(&self, now: DateTime<Utc>) -> Option<f64> {
+        run_seconds(
+            self.start_time.as_deref(),
+            self.end_time.as_deref(),
+            !self.is_terminal(),
+            self.total_run_time,
+            now,
+        )
+    }
+
     pub fn duration_str(&self) -> String {
-        let secs = self.estimated_run_time as u64;
-        if secs < 60 {
-            format!("{}s", secs)
-        } else {
-            format!("{}m {:02}s", secs / 60, secs % 60)
+        duration_or_dash(self.duration_at(Utc::now()))
+    }
+
+    /// Seconds between the expected start and the actual start, or `now` for
+    /// a run still waiting for a worker. `None` for runs not yet due.
+    pub fn queued_at(&self, now: DateTime<Utc>) -> Option<f64> {
+        let expected = parse_time(self.expected_start_time.as_deref())?;
+        let (started, waiting) = match parse_time(self.start_time.as_deref()) {
+            Some(started) => (started, false),
+            None if matches!(self.state_type.as_str(), "SCHEDULED" | "PENDING") => (now, true),
+            None => return None,
+        };
+        if started < expected {
+            // Not due yet, or picked up ahead of schedule
+            return (!waiting).then_some(0.0);
         }
+        Some((started - expected).num_milliseconds() as f64 / 1000.0)
+    }
+
+    pub fn queued_str(&self) -> String {
+        duration_or_dash(self.queued_at(Utc::now()))
     }
 
     pub fn short_id(&self) -> &str {
-        if self.id.len() >= 8 {
-            &self.id[..8]
-        } else {
-            &self.id
-        }
+        short_id(&self.id)
     }
 
-    pub fn start_time_short(&self) -> String {
-        match &self.start_time {
-            Some(t) => {
-                if t.len() >= 16 {
-                    t[..16].replace('T', " ")
-                } else {
-                    t.clone()
-                }
-            }
-            None => "-".to_string(),
+    pub fn start_time_short(&self, time: TimeDisplay) -> String {
+        time.table_time(self.start_time.as_deref())
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct TaskRun {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub flow_run_id: Option<String>,
+    #[serde(default)]
+    pub state_type: String,
+    #[serde(default)]
+    pub state_name: String,
+    #[serde(default)]
+    pub state: Option<StateDetail>,
+    #[serde(default)]
+    pub start_time: Option<String>,
+    #[serde(default)]
+    pub end_time: Option<String>,
+    #[serde(default)]
+    pub total_run_time: f64,
+    #[serde(default)]
+    pub estimated_run_time: f64,
+}
+
+impl TaskRun {
+    pub fn is_failure(&self) -> bool {
+        matches!(self.state_type.as_str(), "FAILED" | "CRASHED")
+    }
+
+    pub fn state_message(&self) -> Option<&str> {
+        self.state
+            .as_ref()
+            .and_then(|s| s.message.as_deref())
+            .filter(|m| !m.is_empty())
+    }
+
+    pub fn duration_str(&self) -> String {
+        let running = !is_terminal_state(&self.state_type);
+        duration_or_dash(run_seconds(
+            self.start_time.as_deref(),
+            self.end_time.as_deref(),
+            running,
+            self.total_run_time,
+            Utc::now(),
+        ))
+    }
+
+    pub fn short_id(&self) -> &str {
+        short_id(&self.id)
+    }
+
+    pub fn start_time_short(&self, time: TimeDisplay) -> String {
+        time.table_time(self.start_time.as_deref())
+    }
+}
+
+fn parse_time(time: Option<&str>) -> Option<DateTime<Utc>> {
+    time.and_then(crate::timefmt::parse_timestamp)
+}
+
+/// Wall-clock run time: `start` to `end`, or to `now` while the run is still
+/// going. Falls back to Prefect's `total_run_time` when the timestamps don't
+/// give an answer.
+fn run_seconds(
+    start: Option<&str>,
+    end: Option<&str>,
+    running: bool,
+    total_run_time: f64,
+    now: DateTime<Utc>,
+) -> Option<f64> {
+    let end = parse_time(end).or(running.then_some(now));
+    match (parse_time(start), end) {
+        (Some(start), Some(end)) if end >= start => {
+            Some((end - start).num_milliseconds() as f64 / 1000.0)
         }
+        _ if total_run_time > 0.0 => Some(total_run_time),
+        _ => None,
     }
 }
 
+fn duration_or_dash(seconds: Option<f64>) -> String {
+    seconds.map_or_else(|| "-".to_string(), format_duration)
+}
+
+fn format_duration(seconds: f64) -> String {
+    let secs = seconds as u64;
+    if secs < 60 {
+        format!("{}s", secs)
+    } else if secs < 3600 {
+        format!("{}m {:02}s", secs / 60, secs % 60)
+    } else {
+        format!("{}h {:02}m", secs / 3600, secs % 3600 / 60)
+    }
+}
+
+/// Whether a Prefect state type is final: the run will not change again.
+pub fn is_terminal_state(state_type: &str) -> bool {
+    matches!(state_type, "COMPLETED" | "FAILED" | "CANCELLED" | "CRASHED")
+}
+
+/// The first 8 characters of an ID, as shown in tables and messages.
+pub fn short_id(id: &str) -> &str {
+    if id.len() >= 8 {
+        &id[..8]
+    } else {
+        id
+    }
+}
+
+/// Prefect's answer to a proposed state change (`POST .../set_state`).
+#[derive(Debug, Deserialize)]
+pub struct OrchestrationResult {
+    /// `ACCEPT`, `REJECT`, `ABORT` or `WAIT`
+    pub status: String,
+    #[serde(default)]
+    pub state: Option<OrchestratedState>,
+    #[serde(default)]
+    pub details: serde_json::Value,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct OrchestratedState {
+    #[serde(default)]
+    pub name: String,
+}
+
+impl OrchestrationResult {
+    pub fn state_name(&self) -> Option<&str> {
+        self.state
+            .as_ref()
+            .map(|s| s.name.as_str())
+            .filter(|name| !name.is_empty())
+    }
+
+    pub fn reason(&self) -> Option<&str> {
+        self.details["reason"].as_str()
+    }
+}
+
+/// The state object Prefect embeds in flow and task runs.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct StateDetail {
+    #[serde(default)]
+    pub message: Option<String>,
+    #[serde(default)]
+    pub timestamp: Option<String>,
+}
+
+/// A flow run with the fields only the detailed `inspect` view needs.
+#[derive(Debug, Deserialize)]
+pub struct FlowRunDetail {
+    #[serde(flatten)]
+    pub run: FlowRun,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub next_scheduled_start_time: Option<String>,
+    #[serde(default)]
+    pub work_pool_name: Option<String>,
+    #[serde(default)]
+    pub work_queue_name: Option<String>,
+    #[serde(default)]
+    pub infrastructure_pid: Option<String>,
+    #[serde(default)]
+    pub run_count: u64,
+    #[serde(default)]
+    pub parent_task_run_id: Option<String>,
+}
+
 #[derive(Debug, Deserialize, Serialize)]
 pub struct LogEntry {
     pub level: u32,
     pub message: String,
     pub timestamp: String,
+    /// Python logger name, e.g. `prefect.flow_runs`
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub flow_run_id: Option<String>,
+    #[serde(default)]
+    pub task_run_id: Option<String>,
+    /// Filled in by pfp, not the API, when logs of several runs are merged
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub flow_run_name: Option<String>,
+}
+
+/// Parse a log level name (`warning`, `ERROR`) or number into Prefect's
+/// numeric level.
+pub fn parse_log_level(input: &str) -> Option<u32> {
+    match input.to_uppercase().as_str() {
+        "TRACE" => Some(5),
+        "DEBUG" => Some(10),
+        "INFO" => Some(20),
+        "WARNING" | "WARN" => Some(30),
+        "ERROR" => Some(40),
+        "CRITICAL" => Some(50),
+        other => other.parse().ok(),
+    }
 }
 
 impl LogEntry {
@@ -216,14 +430,59 @@ mod tests {
         assert_eq!(fr.duration_str(), "2m 05s");
     }
 
+    fn at(ts: &str) -> DateTime<Utc> {
+        crate::timefmt::parse_timestamp(ts).unwrap()
+    }
+
     #[test]
-    fn flow_run_duration_uses_estimated_run_time_while_running() {
+    fn flow_run_duration_counts_up_to_now_while_running() {
         let fr: FlowRun = serde_json::from_value(json!({
             "id": "a", "name": "r", "state_type": "RUNNING",
+            "start_time": "2026-02-21T17:34:00Z",
             "total_run_time": 0.0, "estimated_run_time": 42.0
         }))
         .unwrap();
-        assert_eq!(fr.duration_str(), "42s");
+        assert_eq!(fr.duration_at(at("2026-02-21T17:36:30Z")), Some(150.0));
+    }
+
+    #[test]
+    fn flow_run_duration_uses_end_time_when_finished() {
+        let fr: FlowRun = serde_json::from_value(json!({
+            "id": "a", "name": "r", "state_type": "COMPLETED",
+            "start_time": "2026-02-21T17:34:00Z", "end_time": "2026-02-21T19:04:30Z",
+            "total_run_time": 12.0
+        }))
+        .unwrap();
+        assert_eq!(fr.duration_at(at("2026-03-01T00:00:00Z")), Some(5430.0));
+        assert_eq!(fr.duration_str(), "1h 30m");
+    }
+
+    #[test]
+    fn flow_run_duration_is_dash_before_start() {
+        let fr: FlowRun = serde_json::from_value(json!({
+            "id": "a", "name": "r", "state_type": "PENDING"
+        }))
+        .unwrap();
+        assert_eq!(fr.duration_str(), "-");
+    }
+
+    #[test]
+    fn flow_run_queued_time() {
+        let started: FlowRun = serde_json::from_value(json!({
+            "id": "a", "name": "r", "state_type": "RUNNING",
+            "expected_start_time": "2026-02-21T17:30:00Z",
+            "start_time": "2026-02-21T17:34:05Z"
+        }))
+        .unwrap();
+        assert_eq!(started.queued_at(at("2026-02-21T18:00:00Z")), Some(245.0));
+
+        let waiting: FlowRun = serde_json::from_value(json!({
+            "id": "b", "name": "r", "state_type": "PENDING",
+            "expected_start_time": "2026-02-21T17:30:00Z"
+        }))
+        .unwrap();
+        assert_eq!(waiting.queued_at(at("2026-02-21T17:31:00Z")), Some(60.0));
+        assert_eq!(waiting.queued_at(at("2026-02-21T17:00:00Z")), None);
     }
 
     #[test]
@@ -241,7 +500,10 @@ mod tests {
             "id": "a", "name": "r", "start_time": "2026-02-21T17:34:05.301Z"
         }))
         .unwrap();
-        assert_eq!(fr.start_time_short(), "2026-02-21 17:34");
+        assert_eq!(
+            fr.start_time_short(TimeDisplay::default()),
+            "2026-02-21 17:34"
+        );
     }
 
     #[test]
@@ -250,7 +512,76 @@ mod tests {
             "id": "a", "name": "r", "start_time": null
         }))
         .unwrap();
-        assert_eq!(fr.start_time_short(), "-");
+        assert_eq!(fr.start_time_short(TimeDisplay::default()), "-");
+    }
+
+    #[test]
+    fn flow_run_detail_deserializes_full_api_object() {
+        let detail: FlowRunDetail = serde_json::from_value(json!({
+            "id": "171a3f55-e9a5-4100-a2dd-efe5c711f847",
+            "name": "cool-run",
+            "state_type": "FAILED",
+            "state_name": "Failed",
+            "state": {"type": "FAILED", "name": "Failed", "message": "Flow run encountered an exception"},
+            "tags": ["manual", "nightly"],
+            "expected_start_time": "2026-02-21T17:34:00Z",
+            "work_pool_name": "docker-prod",
+            "work_queue_name": "default",
+            "infrastructure_pid": "host:1234",
+            "run_count": 2,
+            "parent_task_run_id": null,
+            "parameters": {"config": {"action": "plan"}}
+        }))
+        .unwrap();
+
+        assert_eq!(detail.run.short_id(), "171a3f55");
+        assert_eq!(detail.run.parameters["config"]["action"], "plan");
+        assert_eq!(detail.tags, vec!["manual", "nightly"]);
+        assert_eq!(
+            detail.run.state_message(),
+            Some("Flow run encountered an exception")
+        );
+        assert_eq!(detail.work_pool_name.as_deref(), Some("docker-prod"));
+        assert_eq!(detail.infrastructure_pid.as_deref(), Some("host:1234"));
+        assert_eq!(detail.run_count, 2);
+        assert!(detail.parent_task_run_id.is_none());
+    }
+
+    #[test]
+    fn flow_run_detail_empty_message_is_none() {
+        let detail: FlowRunDetail = serde_json::from_value(json!({
+            "id": "a", "name": "r", "state": {"message": ""}
+        }))
+        .unwrap();
+        assert!(detail.run.state_message().is_none());
+    }
+
+    #[test]
+    fn task_run_failure_and_message() {
+        let task: TaskRun = serde_json::from_value(json!({
+            "id": "0f1e2d3c-aaaa-4bbb-8ccc-123456789abc",
+            "name": "terraform_apply-0",
+            "flow_run_id": "run-1",
+            "state_type": "FAILED",
+            "state_name": "Failed",
+            "state": {"message": "Task run encountered an exception"},
+            "start_time": "2026-02-21T17:34:05.301Z",
+            "end_time": "2026-02-21T17:35:10.301Z",
+            "estimated_run_time": 65.0
+        }))
+        .unwrap();
+
+        assert!(task.is_failure());
+        assert_eq!(
+            task.state_message(),
+            Some("Task run encountered an exception")
+        );
+        assert_eq!(task.short_id(), "0f1e2d3c");
+        assert_eq!(
+            task.start_time_short(TimeDisplay::default()),
+            "2026-02-21 17:34"
+        );
+        assert_eq!(task.duration_str(), "1m 05s");
     }
 
     #[test]
@@ -263,6 +594,28 @@ mod tests {
         assert_eq!(entry.level_name(), "INFO");
     }
 
+    #[test]
+    fn log_entry_keeps_logger_and_run_ids() {
+        let entry: LogEntry = serde_json::from_value(json!({
+            "level": 40, "message": "boom", "timestamp": "2026-02-21T17:34:05.301Z",
+            "name": "prefect.task_runs", "flow_run_id": "run-1", "task_run_id": "task-1"
+        }))
+        .unwrap();
+        let line = serde_json::to_string(&entry).unwrap();
+        assert!(line.contains(r#""name":"prefect.task_runs""#));
+        assert!(line.contains(r#""flow_run_id":"run-1""#));
+        assert!(line.contains(r#""task_run_id":"task-1""#));
+    }
+
+    #[test]
+    fn parse_log_level_names_and_numbers() {
+        assert_eq!(parse_log_level("warning"), Some(30));
+        assert_eq!(parse_log_level("WARN"), Some(30));
+        assert_eq!(parse_log_level("Error"), Some(40));
+        assert_eq!(parse_log_level("25"), Some(25));
+        assert_eq!(parse_log_level("loud"), None);
+    }
+
     #[test]
     fn deployment_deserializes_parameter_openapi_schema() {
         let d: Deployment = serde_json::from_value(json!({
@@ -0,0 +1,230 @@
+use crate::models::FlowRun;
+use crate::settings::Notifier;
+use crate::timespec;
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+/// Tell every notifier that a watched run ended. `value` is the final flow
+/// run as the API returned it. Best-effort: failures are printed, never
+/// returned, so they cannot change the command's exit code.
+pub async fn notify_all(notifiers: &[Notifier], run: &FlowRun, value: &Value) {
+    for notifier in notifiers {
+        let outcome = match notifier {
+            Notifier::Desktop => desktop(run).await,
+            Notifier::Webhook {
+                url,
+                headers,
+                timeout,
+            } => webhook(url, headers, timeout, value).await,
+            Notifier::Command { command, timeout } => hook(command, timeout, run).await,
+        };
+        if let Err(e) = outcome {
+            eprintln!("Warning: notification failed: {}", e);
+        }
+    }
+}
+
+/// Summary line for desktop notifications, e.g. `cool-run: Failed`.
+fn summary(run: &FlowRun) -> String {
+    format!("{}: {}", run.name, run.state_name)
+}
+
+fn desktop_args(run: &FlowRun) -> Vec<String> {
+    let urgency = if run.is_success() {
+        "normal"
+    } else {
+        "critical"
+    };
+    let body = run
+        .state_message()
+        .map_or_else(|| format!("Flow run {}", run.short_id()), str::to_string);
+    vec![
+        "--app-name=pfp".to_string(),
+        format!("--urgency={}", urgency),
+        summary(run),
+        body,
+    ]
+}
+
+async fn desktop(run: &FlowRun) -> Result<(), String> {
+    let status = tokio::process::Command::new("notify-send")
+        .args(desktop_args(run))
+        .status()
+        .await
+        .map_err(|e| format!("cannot run notify-send: {}", e))?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(format!("notify-send exited with {}", status))
+    }
+}
+
+async fn webhook(
+    url: &str,
+    headers: &BTreeMap<String, String>,
+    timeout: &str,
+    value: &Value,
+) -> Result<(), String> {
+    let timeout = timespec::parse_duration(timeout)?
+        .to_std()
+        .map_err(|e| e.to_string())?;
+    let client = reqwest::Client::builder()
+        .timeout(timeout)
+        .build()
+        .map_err(|e| e.to_string())?;
+    let mut request = client.post(url).json(value);
+    for (name, header) in headers {
+        request = request.header(name, header);
+    }
+    let response = request
+        .send()
+        .await
+        .map_err(|e| format!("webhook {}: {}", url, e))?;
+    response
+        .error_for_status()
+        .map_err(|e| format!("webhook {}: {}", url, e))?;
+    Ok(())
+}
+
+/// Environment describing the run for command hooks.
+fn hook_env(run: &FlowRun) -> Vec<(&'static str, String)> {
+    vec![
+        ("PFP_FLOW_RUN_ID", run.id.clone()),
+        ("PFP_FLOW_RUN_NAME", run.name.clone()),
+        ("PFP_STATE_TYPE", run.state_type.clone()),
+        ("PFP_STATE_NAME", run.state_name.clone()),
+        (
+            "PFP_STATE_MESSAGE",
+            run.state_message().unwrap_or_default().to_string(),
+        ),
+        (
+            "PFP_DEPLOYMENT_ID",
+            run.deployment_id.clone().unwrap_or_default(),
+        ),
+    ]
+}
+
+/// Run a command hook. Its output goes to stderr so it never mixes with
+/// pfp's structured stdout; a hook still running after `timeout` is killed.
+async fn hook(command: &[String], timeout: &str, run: &FlowRun) -> Result<(), String> {
+    let Some((program, args)) = command.split_first() else {
+        return Err("command notifier has an empty command".to_string());
+    };
+    let limit = timespec::parse_duration(timeout)?
+        .to_std()
+        .map_err(|e| e.to_string())?;
+    let mut child = tokio::process::Command::new(program)
+        .args(args)
+        .envs(hook_env(run))
+        .stdin(std::process::Stdio::null())
+        .stdout(std::io::stderr())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|e| format!("cannot run {}: {}", program, e))?;
+    let status = tokio::time::timeout(limit, child.wait())
+        .await
+        .map_err(|_| format!("{} still running after {}, stopped it", program, timeout))?
+        .map_err(|e| format!("cannot run {}: {}", program, e))?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(format!("{} exited with {}", program, status))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn failed_run() -> Value {
+        json!({
+            "id": "run-1aaaaaaa", "name": "cool-run", "state_type": "FAILED",
+            "state_name": "Failed", "deployment_id": "dep-1",
+            "state": {"type": "FAILED", "message": "boom"}
+        })
+    }
+
+    fn flow_run(value: &Value) -> FlowRun {
+        serde_json::from_value(value.clone()).unwrap()
+    }
+
+    #[test]
+    fn desktop_notification_is_urgent_on_failure() {
+        let value = failed_run();
+        assert_eq!(
+            desktop_args(&flow_run(&value)),
+            vec![
+                "--app-name=pfp",
+                "--urgency=critical",
+                "cool-run: Failed",
+                "boom"
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn webhook_posts_final_flow_run() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/hook")
+            .match_header("x-token", "abc")
+            .match_body(mockito::Matcher::PartialJsonString(
+                r#"{"id": "run-1aaaaaaa", "state_name": "Failed"}"#.to_string(),
+            ))
+            .with_status(200)
+            .create_async()
+            .await;
+        let value = failed_run();
+        let notifier = Notifier::Webhook {
+            url: format!("{}/hook", server.url()),
+            headers: BTreeMap::from([("x-token".to_string(), "abc".to_string())]),
+            timeout: "5s".to_string(),
+        };
+        notify_all(&[notifier], &flow_run(&value), &value).await;
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn webhook_failure_names_the_url() {
+        let value = failed_run();
+        let result = webhook("http://127.0.0.1:1/hook", &BTreeMap::new(), "1s", &value).await;
+        assert!(result.unwrap_err().contains("127.0.0.1:1"));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn command_hook_sees_run_environment() {
+        let dir = tempfile::tempdir().unwrap();
+        let out = dir.path().join("hook.txt");
+        let command = vec![
+            "sh".to_string(),
+            "-c".to_string(),
+            format!(
+                "echo \"$PFP_FLOW_RUN_NAME $PFP_STATE_NAME $PFP_DEPLOYMENT_ID\" > {}",
+                out.display()
+            ),
+        ];
+        let value = failed_run();
+        assert!(hook(&command, "10s", &flow_run(&value)).await.is_ok());
+        assert_eq!(
+            std::fs::read_to_string(&out).unwrap(),
+            "cool-run Failed dep-1\n"
+        );
+        assert!(hook(&["false".to_string()], "10s", &flow_run(&value))
+            .await
+            .is_err());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn command_hook_is_stopped_after_its_timeout() {
+        let command = vec!["sleep".to_string(), "30".to_string()];
+        let started = std::time::Instant::now();
+        let err = hook(&command, "1s", &flow_run(&failed_run()))
+            .await
+            .unwrap_err();
+        assert!(err.contains("still running after 1s"), "{err}");
+        assert!(started.elapsed() < std::time::Duration::from_secs(10));
+    }
+}
@@ -1,6 +1,33 @@
-use crate::models::{Deployment, FlowRun, LogEntry, WorkPool};
+use crate::models::{Deployment, FlowRun, FlowRunDetail, LogEntry, TaskRun, WorkPool};
+use crate::timefmt::TimeDisplay;
 use colored::Colorize;
 
+/// How list-producing commands render their results.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    Table,
+    /// One pretty-printed JSON document
+    Json,
+    /// One compact JSON object per line (NDJSON), safe to stream
+    Jsonl,
+}
+
+impl OutputFormat {
+    pub fn from_flags(json: bool, jsonl: bool) -> Self {
+        if jsonl {
+            Self::Jsonl
+        } else if json {
+            Self::Json
+        } else {
+            Self::Table
+        }
+    }
+
+    pub fn is_structured(self) -> bool {
+        self != Self::Table
+    }
+}
+
 pub fn state_color(state: &str) -> colored::ColoredString {
     match state.to_uppercase().as_str() {
         "COMPLETED" | "ACTIVE" => state.green(),
@@ -25,24 +52,122 @@ pub fn print_deployments_table(deployments: &[Deployment]) {
     }
 }
 
-pub fn print_flow_runs_table(runs: &[FlowRun]) {
+/// Flow-run table; `queued` adds a QUEUED column showing how long each run
+/// waited for a worker after its expected start.
+pub fn print_flow_runs_table(runs: &[FlowRun], queued: bool, time: TimeDisplay) {
+    let queued_header = if queued {
+        format!("{:<10} ", "QUEUED")
+    } else {
+        String::new()
+    };
     println!(
-        "{:<26} {:<12} {:<20} {:<10} ID",
-        "FLOW RUN", "STATE", "STARTED", "DURATION"
+        "{:<26} {:<12} {:<20} {:<10} {}ID",
+        "FLOW RUN", "STATE", "STARTED", "DURATION", queued_header
     );
     for r in runs {
         let state = state_color(&r.state_type);
+        let queued_cell = if queued {
+            format!("{:<10} ", r.queued_str())
+        } else {
+            String::new()
+        };
         println!(
-            "{:<26} {:<12} {:<20} {:<10} {}",
+            "{:<26} {:<12} {:<20} {:<10} {}{}",
             truncate(&r.name, 26),
             state,
-            r.start_time_short(),
+            r.start_time_short(time),
             r.duration_str(),
+            queued_cell,
             r.short_id(),
         );
     }
 }
 
+pub fn print_task_runs_table(tasks: &[TaskRun], time: TimeDisplay) {
+    println!(
+        "{:<36} {:<12} {:<20} {:<10} ID",
+        "TASK RUN", "STATE", "STARTED", "DURATION"
+    );
+    for t in tasks {
+        let state = state_color(&t.state_type);
+        println!(
+            "{:<36} {:<12} {:<20} {:<10} {}",
+            truncate(&t.name, 36),
+            state,
+            t.start_time_short(time),
+            t.duration_str(),
+            t.short_id(),
+        );
+        if t.is_failure() {
+            if let Some(message) = t.state_message() {
+                println!("  {}", message.red());
+            }
+        }
+    }
+}
+
+/// Label/value rows for the detailed flow-run view, in display order.
+fn flow_run_detail_rows(detail: &FlowRunDetail, time: TimeDisplay) -> Vec<(&'static str, String)> {
+    let run = &detail.run;
+    let or_dash = |value: Option<&str>| value.unwrap_or("-").to_string();
+    vec![
+        ("ID", run.id.clone()),
+        ("Name", run.name.clone()),
+        ("State", format!("{} ({})", run.state_type, run.state_name)),
+        ("Message", or_dash(run.state_message())),
+        ("Deployment", or_dash(run.deployment_id.as_deref())),
+        ("Work pool", or_dash(detail.work_pool_name.as_deref())),
+        ("Work queue", or_dash(detail.work_queue_name.as_deref())),
+        (
+            "Tags",
+            if detail.tags.is_empty() {
+                "-".to_string()
+            } else {
+                detail.tags.join(", ")
+            },
+        ),
+        (
+            "Scheduled",
+            time.detail_time(detail.next_scheduled_start_time.as_deref()),
+        ),
+        (
+            "Expected start",
+            time.detail_time(run.expected_start_time.as_deref()),
+        ),
+        ("Started", time.detail_time(run.start_time.as_deref())),
+        ("Ended", time.detail_time(run.end_time.as_deref())),
+        ("Duration", run.duration_str()),
+        ("Run count", detail.run_count.to_string()),
+        ("Infra PID", or_dash(detail.infrastructure_pid.as_deref())),
+        ("Parent task", or_dash(detail.parent_task_run_id.as_deref())),
+    ]
+}
+
+pub fn print_flow_run_detail(detail: &FlowRunDetail, time: TimeDisplay) {
+    for (label, value) in flow_run_detail_rows(detail, time) {
+        let value = if label == "State" {
+            format!(
+                "{} ({})",
+                state_color(&detail.run.state_type),
+                detail.run.state_name
+            )
+        } else {
+            value
+        };
+        println!("{:<16}{}", format!("{}:", label), value);
+    }
+
+    let parameters = &detail.run.parameters;
+    if parameters.as_object().is_some_and(|p| !p.is_empty()) {
+        println!("Parameters:");
+        for line in serde_json::to_string_pretty(parameters).unwrap().lines() {
+            println!("  {}", line);
+        }
+    } else {
+        println!("{:<16}-", "Parameters:");
+    }
+}
+
 pub fn print_work_pool(pool: &WorkPool) {
     let status = pool.status.as_deref().unwrap_or("UNKNOWN");
     let status = state_color(status);
@@ -55,30 +180,36 @@ pub fn print_work_pool(pool: &WorkPool) {
     );
 }
 
-pub fn print_logs(logs: &[LogEntry]) {
+pub fn print_logs(logs: &[LogEntry], time: TimeDisplay) {
     for log in logs {
-        let ts = if log.timestamp.len() >= 19 {
-            &log.timestamp[..19]
-        } else {
-            &log.timestamp
-        };
-        let name = log.level_name();
-        let level = match name {
-            "ERROR" | "CRITICAL" => name.red(),
-            "WARNING" => name.yellow(),
-            "INFO" => name.blue(),
-            _ => name.dimmed(),
-        };
-        println!("{} | {:<8} | {}", ts, level, log.message);
+        println!("{}", log_line(log, true, time));
     }
 }
 
-pub fn print_watch_state(state_name: &str, timestamp: &str) {
-    let ts = if timestamp.len() >= 19 {
-        &timestamp[11..19]
-    } else {
-        timestamp
+/// One log entry formatted as `pfp logs` prints it. `color` is off when the
+/// line goes to a file.
+pub fn log_line(log: &LogEntry, color: bool, time: TimeDisplay) -> String {
+    let ts = time.log_time(&log.timestamp);
+    let name = log.level_name();
+    let level = match name {
+        _ if !color => name.normal(),
+        "ERROR" | "CRITICAL" => name.red(),
+        "WARNING" => name.yellow(),
+        "INFO" => name.blue(),
+        _ => name.dimmed(),
     };
+    match &log.flow_run_name {
+        Some(run) => {
+            let run = truncate(run, 26);
+            let run = if color { run.cyan() } else { run.normal() };
+            format!("{:<26} | {} | {:<8} | {}", run, ts, level, log.message)
+        }
+        None => format!("{} | {:<8} | {}", ts, level, log.message),
+    }
+}
+
+pub fn print_watch_state(state_name: &str, timestamp: &str, time: TimeDisplay) {
+    let ts = time.clock_time(timestamp);
     let state = state_color(state_name);
     println!("{} | {}", ts, state);
 }
@@ -87,11 +218,22 @@ pub fn print_json<T: serde::Serialize>(value: &T) {
     println!("{}", serde_json::to_string_pretty(value).unwrap());
 }
 
-fn truncate(s: &str, max_len: usize) -> String {
-    if s.len() <= max_len {
+pub fn print_json_line<T: serde::Serialize>(value: &T) {
+    println!("{}", serde_json::to_string(value).unwrap());
+}
+
+pub fn print_json_lines<T: serde::Serialize>(values: &[T]) {
+    for value in values {
+        print_json_line(value);
+    }
+}
+
+pub fn truncate(s: &str, max_len: usize) -> String {
+    if s.chars().count() <= max_len {
         s.to_string()
     } else {
-        format!("{}...", &s[..max_len - 3])
+        let kept: String = s.chars().take(max_len - 3).collect();
+        format!("{}...", kept)
     }
 }
 
@@ -110,6 +252,60 @@ mod tests {
         assert_eq!(state_color("completed").deref(), "completed");
     }
 
+    #[test]
+    fn flow_run_detail_rows_fill_missing_fields_with_dash() {
+        let detail: FlowRunDetail = serde_json::from_value(serde_json::json!({
+            "id": "abc", "name": "run", "state_type": "FAILED", "state_name": "Failed",
+            "state": {"message": "boom"}, "tags": ["manual", "nightly"]
+        }))
+        .unwrap();
+        let rows = flow_run_detail_rows(&detail, TimeDisplay::default());
+        let get = |label: &str| rows.iter().find(|(l, _)| *l == label).unwrap().1.clone();
+
+        assert_eq!(get("State"), "FAILED (Failed)");
+        assert_eq!(get("Message"), "boom");
+        assert_eq!(get("Tags"), "manual, nightly");
+        assert_eq!(get("Work pool"), "-");
+        assert_eq!(get("Run count"), "0");
+    }
+
+    #[test]
+    fn flow_run_detail_times_follow_the_display_zone() {
+        let detail: FlowRunDetail = serde_json::from_value(serde_json::json!({
+            "id": "abc", "name": "run", "state_type": "RUNNING", "state_name": "Running",
+            "start_time": "2026-02-21T17:34:05.301Z"
+        }))
+        .unwrap();
+        let berlin = TimeDisplay {
+            zone: crate::timefmt::Zone::Named(chrono_tz::Europe::Berlin),
+            absolute: false,
+        };
+        let rows = flow_run_detail_rows(&detail, berlin);
+        let get = |label: &str| rows.iter().find(|(l, _)| *l == label).unwrap().1.clone();
+
+        assert_eq!(get("Started"), "2026-02-21 18:34:05 +01:00");
+        assert_eq!(get("Ended"), "-");
+    }
+
+    #[test]
+    fn plain_log_line_has_no_escape_codes() {
+        let log: LogEntry = serde_json::from_value(serde_json::json!({
+            "level": 40, "message": "boom", "timestamp": "2026-02-21T17:34:05.301Z"
+        }))
+        .unwrap();
+        assert_eq!(
+            log_line(&log, false, TimeDisplay::default()),
+            "2026-02-21T17:34:05 | ERROR    | boom"
+        );
+    }
+
+    #[test]
+    fn output_format_jsonl_wins_over_json() {
+        assert_eq!(OutputFormat::from_flags(true, true), OutputFormat::Jsonl);
+        assert_eq!(OutputFormat::from_flags(true, false), OutputFormat::Json);
+        assert_eq!(OutputFormat::from_flags(false, false), OutputFormat::Table);
+    }
+
     #[test]
     fn truncate_short_unchanged() {
         assert_eq!(truncate("hello", 10), "hello");
@@ -39,6 +39,57 @@ pub fn parse_params(content: &str) -> Result<Value, String> {
     Ok(value)
 }
 
+/// Parse a `--params-file` payload: one parameters object, or a non-empty
+/// JSON array of them to create one flow run per object.
+pub fn parse_payload(content: &str) -> Result<Value, String> {
+    let value: Value = serde_json::from_str(content)
+        .map_err(|e| format!("Invalid JSON in params payload: {}", e))?;
+    match &value {
+        Value::Array(items) if items.is_empty() => {
+            Err("Params payload array must not be empty".to_string())
+        }
+        Value::Array(items) => match items.iter().position(|item| !item.is_object()) {
+            Some(index) => Err(format!(
+                "Params payload array item {} must be a JSON object",
+                index + 1
+            )),
+            None => Ok(value),
+        },
+        _ => parse_params(content),
+    }
+}
+
+/// Expand `--matrix key=v1,v2` axes into the `key=value` sets of every
+/// combination, the first axis varying slowest.
+pub fn expand_matrix(axes: &[String]) -> Result<Vec<Vec<String>>, String> {
+    let mut combinations: Vec<Vec<String>> = vec![Vec::new()];
+    let mut keys: Vec<&str> = Vec::new();
+    for axis in axes {
+        let (key, values) = axis
+            .split_once('=')
+            .ok_or_else(|| format!("Invalid --matrix format '{}', expected key=v1,v2", axis))?;
+        if keys.contains(&key) {
+            return Err(format!("--matrix key '{}' given more than once", key));
+        }
+        keys.push(key);
+        let values: Vec<&str> = values.split(',').filter(|v| !v.is_empty()).collect();
+        if values.is_empty() {
+            return Err(format!("--matrix key '{}' has no values", key));
+        }
+        combinations = combinations
+            .into_iter()
+            .flat_map(|sets| {
+                values.iter().map(move |value| {
+                    let mut sets = sets.clone();
+                    sets.push(format!("{}={}", key, value));
+                    sets
+                })
+            })
+            .collect();
+    }
+    Ok(combinations)
+}
+
 /// Merge `overrides` into `base` (deep merge at each level).
 pub fn merge_params(base: &Value, overrides: &Value) -> Value {
     match (base, overrides) {
@@ -54,7 +105,9 @@ pub fn merge_params(base: &Value, overrides: &Value) -> Value {
     }
 }
 
-fn auto_type(val: &str) -> Value {
+/// Type a `--set` value: booleans, numbers and JSON arrays or objects are
+/// parsed, anything else stays a string.
+pub fn auto_type(val: &str) -> Value {
     if val == "true" {
         return Value::Bool(true);
     }
@@ -151,6 +204,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::approx_constant)]
     fn auto_type_float() {
         assert_eq!(auto_type("3.14"), json!(3.14));
     }
@@ -247,4 +301,43 @@ mod tests {
         let result = parse_params("42");
         assert!(result.is_err());
     }
+
+    #[test]
+    fn parse_payload_accepts_object_or_list_of_objects() {
+        assert_eq!(parse_payload(r#"{"a": 1}"#).unwrap(), json!({"a": 1}));
+        assert_eq!(
+            parse_payload(r#"[{"a": 1}, {"a": 2}]"#).unwrap(),
+            json!([{"a": 1}, {"a": 2}])
+        );
+        assert!(parse_payload("[]").unwrap_err().contains("empty"));
+        assert!(parse_payload(r#"[{"a": 1}, "b"]"#)
+            .unwrap_err()
+            .contains("item 2"));
+        assert!(parse_payload("42").is_err());
+    }
+
+    #[test]
+    fn expand_matrix_builds_every_combination() {
+        let axes = vec![
+            "environment=dev,prod".to_string(),
+            "config.dry_run=true,false".to_string(),
+        ];
+        assert_eq!(
+            expand_matrix(&axes).unwrap(),
+            vec![
+                vec!["environment=dev", "config.dry_run=true"],
+                vec!["environment=dev", "config.dry_run=false"],
+                vec!["environment=prod", "config.dry_run=true"],
+                vec!["environment=prod", "config.dry_run=false"],
+            ]
+        );
+        assert_eq!(expand_matrix(&[]).unwrap(), vec![Vec::<String>::new()]);
+    }
+
+    #[test]
+    fn expand_matrix_rejects_bad_axes() {
+        assert!(expand_matrix(&["environment".to_string()]).is_err());
+        assert!(expand_matrix(&["environment=".to_string()]).is_err());
+        assert!(expand_matrix(&["a=1".to_string(), "a=2".to_string()]).is_err());
+    }
 }
@@ -0,0 +1,331 @@
+use crate::error::{PfpError, Result};
+use crate::settings::{glob_match, matches_deployment};
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+const POLICY_PATH_ENV: &str = "PFP_POLICY";
+
+/// What pfp may change, read from `~/.pfp/policy.toml` (or `$PFP_POLICY`).
+/// A list that is left out places no restriction; an empty list allows
+/// nothing. Patterns may use `*`, and deployment patterns match either
+/// `flow/deployment` or the bare deployment name.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Policy {
+    #[serde(default)]
+    pub run: RunPolicy,
+    #[serde(default)]
+    pub pause: PausePolicy,
+    #[serde(default)]
+    pub cancel: CancelPolicy,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RunPolicy {
+    /// Deployments that may be run
+    pub deployments: Option<Vec<String>>,
+    #[serde(default)]
+    pub params: Vec<ParamRule>,
+}
+
+/// Parameter limits for the deployments matching `deployment`.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ParamRule {
+    pub deployment: String,
+    /// Dotted parameter keys that may be set with `--set` or `--params-file`
+    pub keys: Option<Vec<String>>,
+    /// Allowed values per dotted key, checked against the parameters the run
+    /// would be created with, deployment defaults included
+    #[serde(default)]
+    pub values: BTreeMap<String, Vec<String>>,
+}
+
+/// Governs `pause`/`resume` and `pool pause`/`resume`.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PausePolicy {
+    pub deployments: Option<Vec<String>>,
+    pub pools: Option<Vec<String>>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CancelPolicy {
+    /// Deployments whose flow runs may be cancelled
+    pub deployments: Option<Vec<String>>,
+}
+
+impl Policy {
+    /// Load the policy file. A missing `~/.pfp/policy.toml` means no policy;
+    /// a missing `$PFP_POLICY` file is an error, so a policy that was asked
+    /// for is never silently skipped. When `~/.pfp/policy.toml` exists it
+    /// wins over `$PFP_POLICY`, so a process cannot lift the user's policy
+    /// by pointing the variable at a laxer file.
+    pub fn load() -> Result<Self> {
+        let default_path = dirs::home_dir()
+            .unwrap_or_else(|| PathBuf::from("."))
+            .join(".pfp")
+            .join("policy.toml");
+        let (path, explicit) = match std::env::var_os(POLICY_PATH_ENV) {
+            Some(path) if default_path.exists() => {
+                eprintln!(
+                    "Warning: ignoring ${}={} because {} exists",
+                    POLICY_PATH_ENV,
+                    PathBuf::from(path).display(),
+                    default_path.display()
+                );
+                (default_path, false)
+            }
+            Some(path) => (PathBuf::from(path), true),
+            None => (default_path, false),
+        };
+        let content = match std::fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound && !explicit => {
+                return Ok(Self::default())
+            }
+            Err(e) => {
+                return Err(PfpError::Config(format!(
+                    "Cannot read policy {}: {}",
+                    path.display(),
+                    e
+                )))
+            }
+        };
+        toml::from_str(&content)
+            .map_err(|e| PfpError::Config(format!("Cannot parse policy {}: {}", path.display(), e)))
+    }
+
+    /// Check a run of `full_name`. `overrides` are the user-supplied
+    /// parameters and `parameters` the merged set the run would get.
+    pub fn check_run(&self, full_name: &str, overrides: &Value, parameters: &Value) -> Result<()> {
+        if !allowed(self.run.deployments.as_deref(), full_name) {
+            return Err(deny(format!("running {} is not allowed", full_name)));
+        }
+
+        let set_keys = leaf_values(overrides);
+        let final_values = leaf_values(parameters);
+        for rule in
+            self.run.params.iter().filter(|rule| {
+                matches_deployment(std::slice::from_ref(&rule.deployment), full_name)
+            })
+        {
+            if let Some(keys) = &rule.keys {
+                if let Some((key, _)) = set_keys
+                    .iter()
+                    .find(|(key, _)| !keys.iter().any(|p| glob_match(p, key)))
+                {
+                    return Err(deny(format!(
+                        "parameter '{}' may not be set for {}",
+                        key, full_name
+                    )));
+                }
+            }
+            // The rejected value stays out of the message: it ends up in the
+            // invocation log, and the key may hold a secret.
+            for (key, allowed_values) in &rule.values {
+                let Some((_, value)) = final_values.iter().find(|(k, _)| k == key) else {
+                    continue;
+                };
+                if !allowed_values.iter().any(|p| glob_match(p, value)) {
+                    return Err(deny(format!(
+                        "this value of '{}' is not allowed for {} (allowed: {})",
+                        key,
+                        full_name,
+                        allowed_values.join(", ")
+                    )));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub fn check_pause_deployment(&self, action: &str, full_name: &str) -> Result<()> {
+        if allowed(self.pause.deployments.as_deref(), full_name) {
+            Ok(())
+        } else {
+            Err(deny(format!("{} {} is not allowed", action, full_name)))
+        }
+    }
+
+    pub fn check_pool(&self, action: &str, name: &str) -> Result<()> {
+        let permitted = match &self.pause.pools {
+            Some(pools) => pools.iter().any(|p| glob_match(p, name)),
+            None => true,
+        };
+        if permitted {
+            Ok(())
+        } else {
+            Err(deny(format!("{} {} is not allowed", action, name)))
+        }
+    }
+
+    /// Whether cancelling needs to know each run's deployment.
+    pub fn restricts_cancel(&self) -> bool {
+        self.cancel.deployments.is_some()
+    }
+
+    /// Check cancelling runs of the given deployments. A run without a
+    /// deployment can only be cancelled when cancels are unrestricted.
+    pub fn check_cancel<'a>(
+        &self,
+        deployments: impl IntoIterator<Item = Option<&'a str>>,
+    ) -> Result<()> {
+        let Some(patterns) = &self.cancel.deployments else {
+            return Ok(());
+        };
+        let mut denied: Vec<&str> = deployments
+            .into_iter()
+            .filter(|name| !name.is_some_and(|name| matches_deployment(patterns, name)))
+            .map(|name| name.unwrap_or("runs without a deployment"))
+            .collect();
+        if denied.is_empty() {
+            return Ok(());
+        }
+        denied.sort_unstable();
+        denied.dedup();
+        Err(deny(format!(
+            "cancelling runs of {} is not allowed",
+            denied.join(", ")
+        )))
+    }
+}
+
+fn allowed(patterns: Option<&[String]>, full_name: &str) -> bool {
+    patterns.is_none_or(|patterns| matches_deployment(patterns, full_name))
+}
+
+fn deny(message: String) -> PfpError {
+    PfpError::PolicyDenied(message)
+}
+
+/// Every leaf of a parameter object as `(dotted.key, value)`. Strings are
+/// taken as-is and other values as JSON text.
+fn leaf_values(params: &Value) -> Vec<(String, String)> {
+    fn walk(prefix: &str, value: &Value, out: &mut Vec<(String, String)>) {
+        match value {
+            Value::Object(map) if !map.is_empty() => {
+                for (key, child) in map {
+                    let path = if prefix.is_empty() {
+                        key.clone()
+                    } else {
+                        format!("{}.{}", prefix, key)
+                    };
+                    walk(&path, child, out);
+                }
+            }
+            Value::String(s) => out.push((prefix.to_string(), s.clone())),
+            other => out.push((prefix.to_string(), other.to_string())),
+        }
+    }
+    let mut out = Vec::new();
+    if params.is_object() {
+        walk("", params, &mut out);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn policy(toml: &str) -> Policy {
+        toml::from_str(toml).unwrap()
+    }
+
+    const AGENT_POLICY: &str = r#"
+[run]
+deployments = ["happy_terraform/*"]
+
+[[run.params]]
+deployment = "happy-terraform-*"
+keys = ["config.action", "config.workspace"]
+values = { "config.action" = ["plan"] }
+
+[pause]
+deployments = []
+pools = ["docker-dev*"]
+
+[cancel]
+deployments = ["*-dev"]
+"#;
+
+    #[test]
+    fn empty_policy_allows_everything() {
+        let policy = Policy::default();
+        assert!(policy
+            .check_run("any/thing", &json!({"a": 1}), &json!({"a": 1}))
+            .is_ok());
+        assert!(policy.check_pause_deployment("Pause", "any/thing").is_ok());
+        assert!(policy.check_pool("Pause work pool", "docker-prod").is_ok());
+        assert!(policy.check_cancel([None]).is_ok());
+    }
+
+    #[test]
+    fn run_allowlist_and_parameter_rules() {
+        let policy = policy(AGENT_POLICY);
+        let name = "happy_terraform/happy-terraform-prod";
+        let plan = json!({"config": {"action": "plan"}});
+
+        assert!(policy.check_run(name, &plan, &plan).is_ok());
+        assert!(policy
+            .check_run("etl/nightly", &json!({}), &json!({}))
+            .is_err());
+
+        let apply = json!({"config": {"action": "apply"}});
+        let err = policy.check_run(name, &apply, &apply).unwrap_err();
+        assert!(err.to_string().contains("'config.action'"));
+        assert!(!err.to_string().contains("apply"));
+
+        let other = json!({"config": {"target": "x"}});
+        let err = policy.check_run(name, &other, &other).unwrap_err();
+        assert!(err.to_string().contains("'config.target'"));
+    }
+
+    #[test]
+    fn value_rules_see_deployment_defaults() {
+        let policy = policy(AGENT_POLICY);
+        let defaults = json!({"config": {"action": "apply"}});
+        assert!(policy
+            .check_run("happy_terraform/happy-terraform-dev", &json!({}), &defaults)
+            .is_err());
+    }
+
+    #[test]
+    fn pause_and_cancel_lists() {
+        let policy = policy(AGENT_POLICY);
+        assert!(policy.check_pause_deployment("Pause", "a/b").is_err());
+        assert!(policy.check_pool("Pause work pool", "docker-dev-2").is_ok());
+        assert!(policy.check_pool("Pause work pool", "docker-prod").is_err());
+
+        assert!(policy.check_cancel([Some("f/app-dev")]).is_ok());
+        let err = policy
+            .check_cancel([Some("f/app-dev"), Some("f/app-prod"), None])
+            .unwrap_err();
+        assert!(matches!(err, PfpError::PolicyDenied(ref msg)
+            if msg.contains("f/app-prod") && msg.contains("without a deployment")));
+    }
+
+    #[test]
+    fn rejects_unknown_sections() {
+        assert!(toml::from_str::<Policy>("[runs]\ndeployments = []\n").is_err());
+    }
+
+    #[test]
+    fn leaf_values_flatten_nested_objects() {
+        let leaves = leaf_values(&json!({"a": {"b": "x", "c": [1, 2]}, "d": true}));
+        assert_eq!(
+            leaves,
+            vec![
+                ("a.b".to_string(), "x".to_string()),
+                ("a.c".to_string(), "[1,2]".to_string()),
+                ("d".to_string(), "true".to_string()),
+            ]
+        );
+    }
+}
@@ -0,0 +1,291 @@
+use crate::params;
+use crate::settings::{glob_match, LogSettings};
+use serde_json::Value;
+use std::collections::BTreeSet;
+
+/// Stands in for every masked value.
+const MASK: &str = "***";
+
+/// Parameter key patterns that are always masked, matched case-insensitively
+/// against each segment of a dotted key.
+const SECRET_KEY_PATTERNS: &[&str] = &["*password*", "*passwd*", "*token*", "*secret*"];
+
+/// Masks parameter values in invocation-log arguments.
+#[derive(Debug)]
+pub struct Redactor {
+    patterns: Vec<String>,
+    paths: BTreeSet<String>,
+    keys_only: bool,
+}
+
+impl Redactor {
+    pub fn new(settings: &LogSettings, paths: BTreeSet<String>) -> Self {
+        let patterns = SECRET_KEY_PATTERNS
+            .iter()
+            .map(|p| p.to_string())
+            .chain(settings.redact.iter().map(|p| p.to_lowercase()))
+            .collect();
+        Self {
+            patterns,
+            paths,
+            keys_only: !settings.param_values,
+        }
+    }
+
+    /// Redact the parameter-bearing fields of a command's logged arguments:
+    /// `sets` and `matrix` (`key=value` strings) and `params_file.payload`,
+    /// one object or a list of them.
+    pub fn redact_args(&self, mut args: Value) -> Value {
+        for field in ["sets", "matrix"] {
+            if let Some(Value::Array(sets)) = args.get_mut(field) {
+                for set in sets.iter_mut() {
+                    if let Value::String(s) = set {
+                        *s = self.redact_set(s);
+                    }
+                }
+            }
+        }
+        if let Some(payload) = args
+            .get_mut("params_file")
+            .and_then(|p| p.get_mut("payload"))
+        {
+            *payload = match &*payload {
+                Value::Array(items) => items.iter().map(|item| self.redact_payload(item)).collect(),
+                single => self.redact_payload(single),
+            };
+        }
+        args
+    }
+
+    fn redact_payload(&self, payload: &Value) -> Value {
+        if self.keys_only {
+            Value::from(leaf_keys(payload))
+        } else {
+            self.redact_params(payload, "")
+        }
+    }
+
+    /// Mask a `key=value` string. A JSON object or array value, typed the
+    /// way `--set` types it, is masked field by field under `key`.
+    fn redact_set(&self, set: &str) -> String {
+        let Some((key, value)) = set.split_once('=') else {
+            return set.to_string();
+        };
+        if self.keys_only {
+            return key.to_string();
+        }
+        if self.is_secret(key) {
+            return format!("{}={}", key, MASK);
+        }
+        match params::auto_type(value) {
+            typed @ (Value::Object(_) | Value::Array(_)) => {
+                format!("{}={}", key, self.redact_params(&typed, key))
+            }
+            _ => set.to_string(),
+        }
+    }
+
+    /// Mask secret fields of a parameter value. List items share their
+    /// list's path, so `a.b` covers every object in a list at `a`.
+    fn redact_params(&self, value: &Value, prefix: &str) -> Value {
+        let map = match value {
+            Value::Object(map) => map,
+            Value::Array(items) => {
+                return items
+                    .iter()
+                    .map(|item| self.redact_params(item, prefix))
+                    .collect();
+            }
+            _ => return value.clone(),
+        };
+        map.iter()
+            .map(|(key, child)| {
+                let path = join(prefix, key);
+                let child = if self.is_secret(&path) {
+                    Value::from(MASK)
+                } else {
+                    self.redact_params(child, &path)
+                };
+                (key.clone(), child)
+            })
+            .collect::<serde_json::Map<_, _>>()
+            .into()
+    }
+
+    /// Whether the value at a dotted key is secret, because one of its
+    /// segments matches a key pattern or the schema marked it (or a parent).
+    fn is_secret(&self, path: &str) -> bool {
+        let lowered = path.to_lowercase();
+        let by_name = lowered
+            .split('.')
+            .any(|segment| self.patterns.iter().any(|p| glob_match(p, segment)));
+        by_name
+            || self.paths.iter().any(|secret| {
+                path == secret
+                    || path
+                        .strip_prefix(secret.as_str())
+                        .is_some_and(|rest| rest.starts_with('.'))
+            })
+    }
+}
+
+fn join(prefix: &str, key: &str) -> String {
+    if prefix.is_empty() {
+        key.to_string()
+    } else {
+        format!("{}.{}", prefix, key)
+    }
+}
+
+/// Dotted keys of every leaf in a parameter object.
+fn leaf_keys(value: &Value) -> Vec<String> {
+    fn walk(prefix: &str, value: &Value, out: &mut Vec<String>) {
+        match value {
+            Value::Object(map) if !map.is_empty() => {
+                for (key, child) in map {
+                    walk(&join(prefix, key), child, out);
+                }
+            }
+            _ if !prefix.is_empty() => out.push(prefix.to_string()),
+            _ => {}
+        }
+    }
+    let mut out = Vec::new();
+    walk("", value, &mut out);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn redactor(param_values: bool, paths: &[&str]) -> Redactor {
+        let settings = LogSettings {
+            param_values,
+            redact: vec!["Vault_*".to_string()],
+            ..Default::default()
+        };
+        Redactor::new(&settings, paths.iter().map(|p| p.to_string()).collect())
+    }
+
+    fn run_args() -> Value {
+        json!({
+            "query": "happy-t",
+            "sets": ["config.action=plan", "config.db_password=hunter2", "api.Token=abc", "vault_path=x/y"],
+            "params_file": {
+                "path": "params.json",
+                "payload": {"config": {"action": "plan", "secrets": {"a": 1}}, "dsn": "pg://u:p@h"}
+            }
+        })
+    }
+
+    #[test]
+    fn masks_secret_keys_and_schema_paths() {
+        let args = redactor(true, &["dsn"]).redact_args(run_args());
+        assert_eq!(
+            args["sets"],
+            json!([
+                "config.action=plan",
+                "config.db_password=***",
+                "api.Token=***",
+                "vault_path=***"
+            ])
+        );
+        assert_eq!(
+            args["params_file"]["payload"],
+            json!({"config": {"action": "plan", "secrets": "***"}, "dsn": "***"})
+        );
+        assert_eq!(args["params_file"]["path"], "params.json");
+        assert_eq!(args["query"], "happy-t");
+    }
+
+    #[test]
+    fn schema_paths_cover_nested_values() {
+        let redactor = redactor(true, &["config"]);
+        assert!(redactor.is_secret("config.action"));
+        assert!(!redactor.is_secret("configuration"));
+    }
+
+    #[test]
+    fn keys_only_mode_drops_every_value() {
+        let args = redactor(false, &[]).redact_args(run_args());
+        assert_eq!(
+            args["sets"],
+            json!([
+                "config.action",
+                "config.db_password",
+                "api.Token",
+                "vault_path"
+            ])
+        );
+        assert_eq!(
+            args["params_file"]["payload"],
+            json!(["config.action", "config.secrets.a", "dsn"])
+        );
+    }
+
+    #[test]
+    fn masks_matrix_values_and_payload_lists() {
+        let args = json!({
+            "matrix": ["environment=dev,prod", "db_password=a,b"],
+            "params_file": {"payload": [{"token": "x", "env": "dev"}, {"token": "y"}]}
+        });
+        let masked = redactor(true, &[]).redact_args(args.clone());
+        assert_eq!(
+            masked["matrix"],
+            json!(["environment=dev,prod", "db_password=***"])
+        );
+        assert_eq!(
+            masked["params_file"]["payload"],
+            json!([{"token": "***", "env": "dev"}, {"token": "***"}])
+        );
+        let keys = redactor(false, &[]).redact_args(args);
+        assert_eq!(
+            keys["params_file"]["payload"],
+            json!([["env", "token"], ["token"]])
+        );
+    }
+
+    #[test]
+    fn args_without_parameters_are_unchanged() {
+        let args = json!({"query": "token-service", "json": true});
+        assert_eq!(redactor(false, &[]).redact_args(args.clone()), args);
+    }
+
+    #[test]
+    fn masks_secrets_inside_lists() {
+        let args = json!({
+            "params_file": {"payload": {"config": {"credentials": [
+                {"path": "kv/a", "token": "x"},
+                {"path": "kv/b", "token": "y"}
+            ], "hosts": ["a", "b"]}, "keys": ["k1"]}}
+        });
+        let masked = redactor(true, &["keys"]).redact_args(args);
+        assert_eq!(
+            masked["params_file"]["payload"],
+            json!({"config": {"credentials": [
+                {"path": "kv/a", "token": "***"},
+                {"path": "kv/b", "token": "***"}
+            ], "hosts": ["a", "b"]}, "keys": "***"})
+        );
+    }
+
+    #[test]
+    fn masks_secrets_nested_in_set_json_values() {
+        let args = json!({"sets": [
+            r#"config={"db_password":"x","action":"plan"}"#,
+            r#"accounts=[{"name":"a","token":"t"}]"#,
+            "config.action={plan",
+        ]});
+        let masked = redactor(true, &[]).redact_args(args);
+        assert_eq!(
+            masked["sets"],
+            json!([
+                r#"config={"action":"plan","db_password":"***"}"#,
+                r#"accounts=[{"name":"a","token":"***"}]"#,
+                "config.action={plan"
+            ])
+        );
+    }
+}
@@ -1,6 +1,7 @@
 use crate::client::PrefectClient;
 use crate::error::{PfpError, Result};
-use crate::models::{Deployment, FlowRun};
+use crate::logger;
+use crate::models::{Deployment, FlowRun, TaskRun};
 
 /// Resolve a user query to a single deployment via unique substring match.
 pub async fn resolve_deployment(client: &PrefectClient, query: &str) -> Result<Deployment> {
@@ -25,7 +26,10 @@ pub async fn resolve_deployment(client: &PrefectClient, query: &str) -> Result<D
                 .iter()
                 .position(|d| d.full_name().contains(query))
                 .unwrap();
-            Ok(deployments.into_iter().nth(idx).unwrap())
+            let deployment = deployments.into_iter().nth(idx).unwrap();
+            logger::record_result("deployment_id", deployment.id.as_str());
+            logger::record_result("deployment", deployment.full_name());
+            Ok(deployment)
         }
         _ => {
             let candidates = matches
@@ -49,6 +53,12 @@ pub fn is_full_uuid(input: &str) -> bool {
 
 /// Resolve a user-provided flow run ID (possibly a short prefix) to a full UUID.
 pub async fn resolve_flow_run(client: &PrefectClient, input: &str) -> Result<String> {
+    let id = find_flow_run(client, input).await?;
+    logger::record_result("flow_run_id", id.as_str());
+    Ok(id)
+}
+
+async fn find_flow_run(client: &PrefectClient, input: &str) -> Result<String> {
     if is_full_uuid(input) {
         return Ok(input.to_string());
     }
@@ -87,6 +97,55 @@ pub async fn resolve_flow_run(client: &PrefectClient, input: &str) -> Result<Str
     }
 }
 
+/// Resolve a task run of one flow run by full UUID, exact name, or a unique
+/// name substring / UUID prefix.
+pub async fn resolve_task_run(
+    client: &PrefectClient,
+    flow_run_id: &str,
+    input: &str,
+) -> Result<String> {
+    if is_full_uuid(input) {
+        return Ok(input.to_string());
+    }
+
+    let values = client.filter_task_runs(flow_run_id).await?;
+    let tasks: Vec<TaskRun> = values
+        .into_iter()
+        .filter_map(|v| serde_json::from_value(v).ok())
+        .collect();
+
+    // An exact name wins even when it is also a substring of other names
+    // (e.g. "apply" vs "apply-0").
+    if let Some(task) = tasks.iter().find(|t| t.name == input) {
+        return Ok(task.id.clone());
+    }
+
+    let input_lower = input.to_lowercase();
+    let matches: Vec<&TaskRun> = tasks
+        .iter()
+        .filter(|t| t.name.contains(input) || t.id.starts_with(&input_lower))
+        .collect();
+
+    match matches.len() {
+        0 => Err(PfpError::NoMatch(format!(
+            "no task run matching '{}' in flow run {}",
+            input, flow_run_id
+        ))),
+        1 => Ok(matches[0].id.clone()),
+        _ => {
+            let candidates = matches
+                .iter()
+                .map(|t| format!("  {} {} ({})", t.short_id(), t.name, t.state_name))
+                .collect::<Vec<_>>()
+                .join("\n");
+            Err(PfpError::AmbiguousMatch {
+                query: input.to_string(),
+                candidates,
+            })
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::models::Deployment;
@@ -258,6 +317,7 @@ mod tests {
         let config = Config {
             api_url: server.url(),
             auth_header: Some("Basic dGVzdDp0ZXN0".to_string()),
+            read_only: false,
         };
         PrefectClient::new(config)
     }
@@ -377,4 +437,61 @@ mod tests {
         assert_eq!(result.unwrap(), "9d9ca60c-abcd-4300-9999-abcdef012345");
         mock.assert_async().await;
     }
+
+    fn mock_task_runs_json() -> String {
+        serde_json::to_string(&vec![
+            json!({
+                "id": "0f1e2d3c-aaaa-4bbb-8ccc-123456789abc",
+                "name": "terraform_plan-0",
+                "state_type": "COMPLETED",
+                "state_name": "Completed"
+            }),
+            json!({
+                "id": "5a6b7c8d-aaaa-4bbb-8ccc-123456789abc",
+                "name": "terraform_apply-0",
+                "state_type": "FAILED",
+                "state_name": "Failed"
+            }),
+        ])
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn resolve_task_run_by_name_substring() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/task_runs/filter")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(mock_task_runs_json())
+            .create_async()
+            .await;
+
+        let client = test_client(&server);
+        let result = super::resolve_task_run(&client, "run-1", "apply").await;
+
+        assert_eq!(result.unwrap(), "5a6b7c8d-aaaa-4bbb-8ccc-123456789abc");
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn resolve_task_run_ambiguous_name() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/task_runs/filter")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(mock_task_runs_json())
+            .create_async()
+            .await;
+
+        let client = test_client(&server);
+        let result = super::resolve_task_run(&client, "run-1", "terraform").await;
+
+        assert!(matches!(
+            result.unwrap_err(),
+            PfpError::AmbiguousMatch { ref candidates, .. } if candidates.contains("terraform_plan-0")
+        ));
+        mock.assert_async().await;
+    }
 }
@@ -0,0 +1,343 @@
+use crate::error::{PfpError, Result};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+const CONFIG_PATH_ENV: &str = "PFP_CONFIG";
+
+/// pfp's own settings, read from `~/.pfp/config.toml` (or `$PFP_CONFIG`).
+/// Prefect connection details stay in Prefect's `profiles.toml`.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Settings {
+    #[serde(default)]
+    pub protect: Protection,
+    #[serde(default)]
+    pub log: LogSettings,
+    /// Fired by `--notify` when a watched run ends
+    #[serde(default)]
+    pub notify: Vec<Notifier>,
+}
+
+/// Deployments and work pools that need a typed confirmation or `--i-know`.
+/// Patterns may use `*` as a wildcard.
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Protection {
+    /// Matched against `flow/deployment` and against the bare deployment name
+    #[serde(default)]
+    pub deployments: Vec<String>,
+    #[serde(default)]
+    pub pools: Vec<String>,
+}
+
+/// What the invocation log may record and where it is written.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LogSettings {
+    /// Record parameter values; when false only their keys are logged
+    #[serde(default = "default_param_values")]
+    pub param_values: bool,
+    /// Extra parameter key patterns whose values are masked
+    #[serde(default)]
+    pub redact: Vec<String>,
+    /// Destinations for each record; an empty list disables logging
+    #[serde(default = "default_sinks")]
+    pub sinks: Vec<LogSink>,
+}
+
+impl Default for LogSettings {
+    fn default() -> Self {
+        Self {
+            param_values: default_param_values(),
+            redact: Vec::new(),
+            sinks: default_sinks(),
+        }
+    }
+}
+
+fn default_param_values() -> bool {
+    true
+}
+
+fn default_sinks() -> Vec<LogSink> {
+    vec![LogSink::File { path: None }]
+}
+
+/// A destination for invocation records.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase", deny_unknown_fields)]
+pub enum LogSink {
+    /// JSON lines in a size-rotated file, `~/.pfp/pfp.jsonl` unless `path`
+    /// is given
+    File {
+        #[serde(default)]
+        path: Option<PathBuf>,
+    },
+    /// One syslog datagram per record on a local socket; journald listens
+    /// on the default `/dev/log` too
+    Syslog {
+        #[serde(default = "default_syslog_socket")]
+        socket: PathBuf,
+    },
+    /// One JSON line per record on a Unix stream socket
+    Unix { socket: PathBuf },
+    /// One JSON POST per record
+    Http {
+        url: String,
+        #[serde(default)]
+        headers: BTreeMap<String, String>,
+        /// Give up after this long, e.g. `2s`
+        #[serde(default = "default_http_timeout")]
+        timeout: String,
+    },
+}
+
+/// How `--notify` reports a watched run's end state.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase", deny_unknown_fields)]
+pub enum Notifier {
+    /// A desktop notification through `notify-send`
+    Desktop,
+    /// A POST of the final flow run JSON
+    Webhook {
+        url: String,
+        #[serde(default)]
+        headers: BTreeMap<String, String>,
+        /// Give up after this long, e.g. `10s`
+        #[serde(default = "default_webhook_timeout")]
+        timeout: String,
+    },
+    /// A program and its arguments, run with `PFP_*` variables describing
+    /// the run
+    Command {
+        command: Vec<String>,
+        /// Stop waiting for the program after this long, e.g. `30s`
+        #[serde(default = "default_command_timeout")]
+        timeout: String,
+    },
+}
+
+fn default_webhook_timeout() -> String {
+    "10s".to_string()
+}
+
+fn default_command_timeout() -> String {
+    "30s".to_string()
+}
+
+fn default_syslog_socket() -> PathBuf {
+    PathBuf::from("/dev/log")
+}
+
+fn default_http_timeout() -> String {
+    "2s".to_string()
+}
+
+impl Settings {
+    pub fn path() -> PathBuf {
+        match std::env::var_os(CONFIG_PATH_ENV) {
+            Some(path) => PathBuf::from(path),
+            None => dirs::home_dir()
+                .unwrap_or_else(|| PathBuf::from("."))
+                .join(".pfp")
+                .join("config.toml"),
+        }
+    }
+
+    /// The notifiers `--notify` fires: those configured, or a desktop
+    /// notification when there are none.
+    pub fn notifiers(&self) -> Vec<Notifier> {
+        if self.notify.is_empty() {
+            vec![Notifier::Desktop]
+        } else {
+            self.notify.clone()
+        }
+    }
+
+    /// Load the settings file. A missing file means default settings.
+    pub fn load() -> Result<Self> {
+        let path = Self::path();
+        let content = match std::fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(e) => {
+                return Err(PfpError::Config(format!(
+                    "Cannot read {}: {}",
+                    path.display(),
+                    e
+                )))
+            }
+        };
+        toml::from_str(&content)
+            .map_err(|e| PfpError::Config(format!("Cannot parse {}: {}", path.display(), e)))
+    }
+}
+
+impl Protection {
+    pub fn is_empty(&self) -> bool {
+        self.deployments.is_empty() && self.pools.is_empty()
+    }
+
+    pub fn deployment(&self, full_name: &str) -> bool {
+        matches_deployment(&self.deployments, full_name)
+    }
+
+    pub fn pool(&self, name: &str) -> bool {
+        self.pools.iter().any(|p| glob_match(p, name))
+    }
+}
+
+/// Whether any pattern matches `flow/deployment` or the bare deployment name.
+pub fn matches_deployment(patterns: &[String], full_name: &str) -> bool {
+    let bare = full_name.rsplit('/').next().unwrap_or(full_name);
+    patterns
+        .iter()
+        .any(|p| glob_match(p, full_name) || glob_match(p, bare))
+}
+
+/// Match `text` against `pattern`, where `*` matches any run of characters.
+pub fn glob_match(pattern: &str, text: &str) -> bool {
+    let parts: Vec<&str> = pattern.split('*').collect();
+    if parts.len() == 1 {
+        return pattern == text;
+    }
+
+    let (first, last) = (parts[0], parts[parts.len() - 1]);
+    if text.len() < first.len() + last.len() || !text.starts_with(first) || !text.ends_with(last) {
+        return false;
+    }
+    let mut rest = &text[first.len()..text.len() - last.len()];
+    for middle in &parts[1..parts.len() - 1] {
+        match rest.find(middle) {
+            Some(index) => rest = &rest[index + middle.len()..],
+            None => return false,
+        }
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn glob_match_wildcards() {
+        assert!(glob_match("*-prod", "happy-terraform-prod"));
+        assert!(glob_match(
+            "happy_*/*-prod",
+            "happy_terraform/happy-terraform-prod"
+        ));
+        assert!(glob_match("*", ""));
+        assert!(glob_match("docker-prod", "docker-prod"));
+        assert!(!glob_match("*-prod", "happy-terraform-dev"));
+        assert!(!glob_match("docker-prod", "docker-prod-2"));
+        assert!(!glob_match("a*a", "a"));
+    }
+
+    #[test]
+    fn protection_matches_full_or_bare_deployment_name() {
+        let protect = Protection {
+            deployments: vec!["*-prod".to_string()],
+            pools: vec!["docker-prod".to_string()],
+        };
+        assert!(protect.deployment("happy_terraform/happy-terraform-prod"));
+        assert!(!protect.deployment("happy_terraform/happy-terraform-dev"));
+        assert!(protect.pool("docker-prod"));
+        assert!(!protect.pool("docker-dev"));
+    }
+
+    #[test]
+    fn settings_parse_protect_table() {
+        let settings: Settings = toml::from_str(
+            r#"
+[protect]
+deployments = ["*-prod"]
+pools = ["docker-prod"]
+"#,
+        )
+        .unwrap();
+        assert_eq!(settings.protect.deployments, vec!["*-prod"]);
+        assert_eq!(settings.protect.pools, vec!["docker-prod"]);
+    }
+
+    #[test]
+    fn settings_parse_log_table() {
+        let settings: Settings =
+            toml::from_str("[log]\nparam_values = false\nredact = [\"vault_*\"]\n").unwrap();
+        assert!(!settings.log.param_values);
+        assert_eq!(settings.log.redact, vec!["vault_*"]);
+        assert!(Settings::default().log.param_values);
+    }
+
+    #[test]
+    fn settings_parse_log_sinks() {
+        let settings: Settings = toml::from_str(
+            r#"
+[log]
+sinks = [
+  { type = "file", path = "/var/log/pfp.jsonl" },
+  { type = "syslog" },
+  { type = "unix", socket = "/run/pfp.sock" },
+  { type = "http", url = "https://logs.example/pfp", headers = { Authorization = "Bearer x" } },
+]
+"#,
+        )
+        .unwrap();
+        assert_eq!(settings.log.sinks.len(), 4);
+        assert_eq!(
+            settings.log.sinks[1],
+            LogSink::Syslog {
+                socket: PathBuf::from("/dev/log")
+            }
+        );
+        assert!(matches!(
+            &settings.log.sinks[3],
+            LogSink::Http { timeout, headers, .. } if timeout == "2s" && headers.len() == 1
+        ));
+
+        let disabled: Settings = toml::from_str("[log]\nsinks = []\n").unwrap();
+        assert!(disabled.log.sinks.is_empty());
+        assert_eq!(
+            Settings::default().log.sinks,
+            vec![LogSink::File { path: None }]
+        );
+        assert!(toml::from_str::<Settings>("[log]\nsinks = [{ type = \"ftp\" }]\n").is_err());
+    }
+
+    #[test]
+    fn settings_parse_notifiers() {
+        let settings: Settings = toml::from_str(
+            r#"
+[[notify]]
+type = "webhook"
+url = "https://hooks.example/pfp"
+
+[[notify]]
+type = "command"
+command = ["say", "done"]
+"#,
+        )
+        .unwrap();
+        assert_eq!(
+            settings.notifiers(),
+            vec![
+                Notifier::Webhook {
+                    url: "https://hooks.example/pfp".to_string(),
+                    headers: BTreeMap::new(),
+                    timeout: "10s".to_string(),
+                },
+                Notifier::Command {
+                    command: vec!["say".to_string(), "done".to_string()],
+                    timeout: "30s".to_string(),
+                },
+            ]
+        );
+        assert_eq!(Settings::default().notifiers(), vec![Notifier::Desktop]);
+    }
+
+    #[test]
+    fn settings_reject_unknown_keys() {
+        assert!(toml::from_str::<Settings>("[protect]\ndeployment = [\"x\"]\n").is_err());
+    }
+}
@@ -0,0 +1,224 @@
+use chrono::{DateTime, Local, NaiveDateTime, Utc};
+use chrono_tz::Tz;
+
+/// Timezone that displayed times are converted to.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Zone {
+    Local,
+    Utc,
+    Named(Tz),
+}
+
+impl Zone {
+    /// Parse `--tz`: `local`, `utc`, or an IANA name such as `Europe/Berlin`.
+    pub fn parse(input: &str) -> Result<Zone, String> {
+        match input.to_lowercase().as_str() {
+            "local" => Ok(Zone::Local),
+            "utc" | "z" => Ok(Zone::Utc),
+            _ => input.parse::<Tz>().map(Zone::Named).map_err(|_| {
+                format!(
+                    "unknown timezone '{}', expected local, utc or an IANA name like Europe/Berlin",
+                    input
+                )
+            }),
+        }
+    }
+}
+
+impl std::fmt::Display for Zone {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Zone::Local => f.write_str("local"),
+            Zone::Utc => f.write_str("utc"),
+            Zone::Named(tz) => f.write_str(tz.name()),
+        }
+    }
+}
+
+/// How times are shown, from the global `--tz` / `--absolute` flags. Built
+/// once in `main` and passed to whatever prints times.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TimeDisplay {
+    pub zone: Zone,
+    /// Show table times as dates rather than "3m ago"
+    pub absolute: bool,
+}
+
+/// UTC dates, which keep output independent of the machine's timezone.
+impl Default for TimeDisplay {
+    fn default() -> Self {
+        TimeDisplay {
+            zone: Zone::Utc,
+            absolute: true,
+        }
+    }
+}
+
+/// Parse an API timestamp. Prefect sends RFC 3339; an offset-less timestamp
+/// is taken as UTC.
+pub fn parse_timestamp(input: &str) -> Option<DateTime<Utc>> {
+    if let Ok(ts) = DateTime::parse_from_rfc3339(input) {
+        return Some(ts.with_timezone(&Utc));
+    }
+    NaiveDateTime::parse_from_str(input, "%Y-%m-%dT%H:%M:%S%.f")
+        .ok()
+        .map(|ts| ts.and_utc())
+}
+
+fn format_in(ts: DateTime<Utc>, zone: Zone, fmt: &str) -> String {
+    match zone {
+        Zone::Local => ts.with_timezone(&Local).format(fmt).to_string(),
+        Zone::Utc => ts.format(fmt).to_string(),
+        Zone::Named(tz) => ts.with_timezone(&tz).format(fmt).to_string(),
+    }
+}
+
+impl TimeDisplay {
+    /// Reformat a timestamp in the display zone; an unparseable value is
+    /// returned unchanged rather than sliced.
+    fn reformat(&self, input: &str, fmt: &str) -> String {
+        match parse_timestamp(input) {
+            Some(ts) => format_in(ts, self.zone, fmt),
+            None => input.to_string(),
+        }
+    }
+
+    /// Timestamp of a log line, to the second. Outside UTC the offset is
+    /// appended, since a bare local time is ambiguous.
+    pub fn log_time(&self, input: &str) -> String {
+        match self.zone {
+            Zone::Utc => self.reformat(input, "%Y-%m-%dT%H:%M:%S"),
+            Zone::Local | Zone::Named(_) => self.reformat(input, "%Y-%m-%dT%H:%M:%S%:z"),
+        }
+    }
+
+    /// Time of day for `--watch` state changes.
+    pub fn clock_time(&self, input: &str) -> String {
+        self.reformat(input, "%H:%M:%S")
+    }
+
+    /// A time in a table column: relative ("3m ago") unless `--absolute`.
+    pub fn table_time(&self, input: Option<&str>) -> String {
+        let Some(input) = input else {
+            return "-".to_string();
+        };
+        if self.absolute {
+            return self.reformat(input, "%Y-%m-%d %H:%M");
+        }
+        match parse_timestamp(input) {
+            Some(ts) => relative(ts, Utc::now()),
+            None => input.to_string(),
+        }
+    }
+
+    /// A full time with its offset, for detail views.
+    pub fn detail_time(&self, input: Option<&str>) -> String {
+        match input {
+            Some(input) => self.reformat(input, "%Y-%m-%d %H:%M:%S %:z"),
+            None => "-".to_string(),
+        }
+    }
+}
+
+/// Age of `ts` relative to `now` in its largest whole unit.
+pub fn relative(ts: DateTime<Utc>, now: DateTime<Utc>) -> String {
+    let secs = (now - ts).num_seconds();
+    let (amount, future) = (secs.unsigned_abs(), secs < 0);
+    let span = match amount {
+        0..=9 => return "just now".to_string(),
+        10..=59 => format!("{}s", amount),
+        60..=3599 => format!("{}m", amount / 60),
+        3600..=86_399 => format!("{}h", amount / 3600),
+        _ => format!("{}d", amount / 86_400),
+    };
+    if future {
+        format!("in {}", span)
+    } else {
+        format!("{} ago", span)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ts(input: &str) -> DateTime<Utc> {
+        parse_timestamp(input).unwrap()
+    }
+
+    #[test]
+    fn zone_parse_accepts_keywords_and_iana_names() {
+        assert_eq!(Zone::parse("local").unwrap(), Zone::Local);
+        assert_eq!(Zone::parse("UTC").unwrap(), Zone::Utc);
+        assert_eq!(
+            Zone::parse("Europe/Berlin").unwrap(),
+            Zone::Named(chrono_tz::Europe::Berlin)
+        );
+        assert!(Zone::parse("Mars/Olympus").unwrap_err().contains("Mars"));
+    }
+
+    #[test]
+    fn parse_timestamp_handles_offsets_and_naive_values() {
+        assert_eq!(
+            ts("2026-02-21T18:34:05+01:00").to_rfc3339(),
+            "2026-02-21T17:34:05+00:00"
+        );
+        assert_eq!(
+            ts("2026-02-21T17:34:05.301").to_rfc3339(),
+            "2026-02-21T17:34:05.301+00:00"
+        );
+        assert!(parse_timestamp("soon").is_none());
+    }
+
+    #[test]
+    fn format_in_named_zone() {
+        let berlin = Zone::Named(chrono_tz::Europe::Berlin);
+        let formatted = format_in(ts("2026-02-21T17:34:05Z"), berlin, "%H:%M:%S");
+        assert_eq!(formatted, "18:34:05");
+    }
+
+    #[test]
+    fn unparseable_timestamps_pass_through() {
+        let display = TimeDisplay::default();
+        assert_eq!(display.log_time("abc"), "abc");
+        assert_eq!(display.clock_time("2026"), "2026");
+        assert_eq!(display.table_time(None), "-");
+    }
+
+    #[test]
+    fn log_and_detail_times_carry_the_offset_outside_utc() {
+        let berlin = TimeDisplay {
+            zone: Zone::Named(chrono_tz::Europe::Berlin),
+            absolute: false,
+        };
+        assert_eq!(
+            berlin.log_time("2026-02-21T17:34:05Z"),
+            "2026-02-21T18:34:05+01:00"
+        );
+        assert_eq!(
+            berlin.detail_time(Some("2026-02-21T17:34:05Z")),
+            "2026-02-21 18:34:05 +01:00"
+        );
+        let utc = TimeDisplay::default();
+        assert_eq!(utc.log_time("2026-02-21T17:34:05Z"), "2026-02-21T17:34:05");
+        assert_eq!(utc.detail_time(None), "-");
+    }
+
+    #[test]
+    fn zone_displays_as_it_is_parsed() {
+        for input in ["local", "utc", "Europe/Berlin"] {
+            assert_eq!(Zone::parse(input).unwrap().to_string(), input);
+        }
+    }
+
+    #[test]
+    fn relative_picks_largest_unit() {
+        let now = ts("2026-03-10T12:00:00Z");
+        assert_eq!(relative(ts("2026-03-10T11:59:55Z"), now), "just now");
+        assert_eq!(relative(ts("2026-03-10T11:59:30Z"), now), "30s ago");
+        assert_eq!(relative(ts("2026-03-10T11:57:00Z"), now), "3m ago");
+        assert_eq!(relative(ts("2026-03-10T09:00:00Z"), now), "3h ago");
+        assert_eq!(relative(ts("2026-03-08T12:00:00Z"), now), "2d ago");
+        assert_eq!(relative(ts("2026-03-10T12:05:00Z"), now), "in 5m");
+    }
+}
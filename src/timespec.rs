@@ -0,0 +1,95 @@
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+
+/// Parse a relative duration such as `90s`, `30m`, `24h`, `7d` or `2w`.
+pub fn parse_duration(input: &str) -> Result<Duration, String> {
+    let input = input.trim();
+    let split = input
+        .find(|c: char| !c.is_ascii_digit())
+        .ok_or_else(|| format!("Invalid duration '{}', expected e.g. 30m, 24h, 7d", input))?;
+    let (amount, unit) = input.split_at(split);
+    let amount: i64 = amount
+        .parse()
+        .map_err(|_| format!("Invalid duration '{}', expected e.g. 30m, 24h, 7d", input))?;
+
+    let duration = match unit {
+        "s" => Duration::try_seconds(amount),
+        "m" => Duration::try_minutes(amount),
+        "h" => Duration::try_hours(amount),
+        "d" => Duration::try_days(amount),
+        "w" => Duration::try_weeks(amount),
+        _ => None,
+    };
+    duration.ok_or_else(|| format!("Invalid duration '{}', expected e.g. 30m, 24h, 7d", input))
+}
+
+/// Parse a time bound for `--since` / `--until`: a relative age measured back
+/// from `now` (`24h`), an RFC 3339 timestamp, or a `YYYY-MM-DD` date
+/// (midnight UTC).
+pub fn parse_time_bound(input: &str, now: DateTime<Utc>) -> Result<DateTime<Utc>, String> {
+    let input = input.trim();
+
+    if let Ok(ts) = DateTime::parse_from_rfc3339(input) {
+        return Ok(ts.with_timezone(&Utc));
+    }
+    if let Ok(date) = NaiveDate::parse_from_str(input, "%Y-%m-%d") {
+        return Ok(date.and_hms_opt(0, 0, 0).unwrap().and_utc());
+    }
+
+    parse_duration(input).map(|age| now - age).map_err(|_| {
+        format!(
+            "Invalid time '{}', expected a duration (24h), an RFC 3339 timestamp or YYYY-MM-DD",
+            input
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn now() -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339("2026-03-10T12:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    #[test]
+    fn duration_units() {
+        assert_eq!(parse_duration("90s").unwrap(), Duration::seconds(90));
+        assert_eq!(parse_duration("30m").unwrap(), Duration::minutes(30));
+        assert_eq!(parse_duration("24h").unwrap(), Duration::hours(24));
+        assert_eq!(parse_duration("7d").unwrap(), Duration::days(7));
+        assert_eq!(parse_duration("2w").unwrap(), Duration::weeks(2));
+    }
+
+    #[test]
+    fn duration_rejects_unknown_unit() {
+        assert!(parse_duration("5y").is_err());
+        assert!(parse_duration("h").is_err());
+        assert!(parse_duration("12").is_err());
+    }
+
+    #[test]
+    fn time_bound_relative() {
+        let bound = parse_time_bound("24h", now()).unwrap();
+        assert_eq!(bound.to_rfc3339(), "2026-03-09T12:00:00+00:00");
+    }
+
+    #[test]
+    fn time_bound_rfc3339_with_offset() {
+        let bound = parse_time_bound("2026-03-10T08:00:00+02:00", now()).unwrap();
+        assert_eq!(bound.to_rfc3339(), "2026-03-10T06:00:00+00:00");
+    }
+
+    #[test]
+    fn time_bound_date_is_midnight_utc() {
+        let bound = parse_time_bound("2026-03-01", now()).unwrap();
+        assert_eq!(bound.to_rfc3339(), "2026-03-01T00:00:00+00:00");
+    }
+
+    #[test]
+    fn time_bound_rejects_garbage() {
+        let err = parse_time_bound("yesterday", now()).unwrap_err();
+        assert!(err.contains("yesterday"));
+    }
+}
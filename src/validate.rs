@@ -167,6 +167,73 @@ fn walk_params(
     }
 }
 
+/// Nesting depth at which `secret_paths` stops, so recursive models end.
+const MAX_SECRET_DEPTH: usize = 8;
+
+/// Whether a schema node marks its value as secret with `format: password`
+/// or `writeOnly`, directly or through `$ref`, `allOf`, `anyOf` or `oneOf`.
+fn is_secret_node(node: &Value, definitions: &Value, depth: usize) -> bool {
+    if depth > MAX_SECRET_DEPTH {
+        return false;
+    }
+    if node.get("format").and_then(Value::as_str) == Some("password")
+        || node.get("writeOnly").and_then(Value::as_bool) == Some(true)
+    {
+        return true;
+    }
+    if let Some(target) = node
+        .get("$ref")
+        .and_then(Value::as_str)
+        .and_then(|r| resolve_ref(r, definitions))
+    {
+        return is_secret_node(target, definitions, depth + 1);
+    }
+    ["allOf", "anyOf", "oneOf"].iter().any(|keyword| {
+        node.get(*keyword)
+            .and_then(Value::as_array)
+            .is_some_and(|items| {
+                items
+                    .iter()
+                    .any(|item| is_secret_node(item, definitions, depth + 1))
+            })
+    })
+}
+
+fn collect_secret_paths(
+    node: &Value,
+    definitions: &Value,
+    path: &str,
+    depth: usize,
+    paths: &mut Vec<String>,
+) {
+    if depth > MAX_SECRET_DEPTH {
+        return;
+    }
+    let Some(props) = resolve_properties(node, definitions, &mut Default::default()) else {
+        return;
+    };
+    for (key, child) in props {
+        let full_path = if path.is_empty() {
+            key
+        } else {
+            format!("{}.{}", path, key)
+        };
+        if is_secret_node(&child, definitions, 0) {
+            paths.push(full_path);
+        } else {
+            collect_secret_paths(&child, definitions, &full_path, depth + 1, paths);
+        }
+    }
+}
+
+/// Dotted paths of the parameters a deployment's schema marks as secret.
+pub fn secret_paths(schema: &Value) -> Vec<String> {
+    let mut paths = Vec::new();
+    collect_secret_paths(schema, get_definitions(schema), "", 0, &mut paths);
+    paths.sort();
+    paths
+}
+
 /// Format the context label for a path.
 fn format_context(path: &str) -> String {
     if path.is_empty() {
@@ -1274,4 +1341,36 @@ mod tests {
         .unwrap();
         assert!(validate_params(&overrides, &schema).is_ok());
     }
+
+    #[test]
+    fn secret_paths_follow_refs_and_nested_models() {
+        let schema = json!({
+            "properties": {
+                "api_token": {"type": "string", "format": "password", "writeOnly": true},
+                "config": {"allOf": [{"$ref": "#/definitions/Config"}]},
+                "name": {"type": "string"}
+            },
+            "definitions": {
+                "Config": {
+                    "properties": {
+                        "db": {"anyOf": [{"$ref": "#/definitions/Secret"}, {"type": "null"}]},
+                        "action": {"type": "string"}
+                    }
+                },
+                "Secret": {"type": "string", "writeOnly": true}
+            }
+        });
+        assert_eq!(secret_paths(&schema), vec!["api_token", "config.db"]);
+    }
+
+    #[test]
+    fn secret_paths_stop_on_recursive_models() {
+        let schema = json!({
+            "properties": {"node": {"$ref": "#/definitions/Node"}},
+            "definitions": {
+                "Node": {"properties": {"child": {"$ref": "#/definitions/Node"}}}
+            }
+        });
+        assert!(secret_paths(&schema).is_empty());
+    }
 }
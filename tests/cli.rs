@@ -37,6 +37,7 @@ fn help_lists_subcommands() {
         .stdout(predicate::str::contains("run"))
         .stdout(predicate::str::contains("runs"))
         .stdout(predicate::str::contains("inspect"))
+        .stdout(predicate::str::contains("tasks"))
         .stdout(predicate::str::contains("logs"))
         .stdout(predicate::str::contains("pause"))
         .stdout(predicate::str::contains("resume"))
@@ -217,6 +218,338 @@ fn logs_tail_flag_is_accepted_by_parser() {
         .stderr(predicate::str::contains("unexpected argument").not());
 }
 
+/// --tail also takes a count of the newest entries to show; following is
+/// spelled --follow / -f.
+#[test]
+fn logs_tail_takes_entry_count() {
+    cargo_bin_cmd!("pfp")
+        .args(["logs", "--help"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("--tail [<N>]"))
+        .stdout(predicate::str::contains("--follow"));
+}
+
+/// --tail N is parsed as a count rather than erroring as a malformed
+/// argument.
+#[test]
+fn logs_tail_count_is_accepted_by_parser() {
+    cargo_bin_cmd!("pfp")
+        .args(["logs", "some-run", "--tail", "20", "-f"])
+        .env("PREFECT_API_URL", "http://127.0.0.1:1")
+        .env_remove("PREFECT_API_AUTH_STRING")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("unexpected argument").not())
+        .stderr(predicate::str::contains("invalid value").not())
+        .stderr(predicate::str::contains("deprecated").not());
+}
+
+/// A bare --tail still follows, but warns that the spelling is deprecated.
+#[test]
+fn logs_bare_tail_warns_and_follows() {
+    cargo_bin_cmd!("pfp")
+        .args(["logs", "some-run", "--tail"])
+        .env("PREFECT_API_URL", "http://127.0.0.1:1")
+        .env_remove("PREFECT_API_AUTH_STRING")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "--tail without a count is deprecated; use --follow (-f)",
+        ));
+}
+
+/// An invalid --level is a usage error caught before any network request.
+#[test]
+fn logs_rejects_unknown_level() {
+    cargo_bin_cmd!("pfp")
+        .args(["logs", "some-run", "--level", "loud"])
+        .env("PREFECT_API_URL", "http://127.0.0.1:1")
+        .env_remove("PREFECT_API_AUTH_STRING")
+        .assert()
+        .failure()
+        .code(2)
+        .stderr(predicate::str::contains("unknown log level 'loud'"));
+}
+
+/// --json prints one array per fetch, which cannot be parsed as a stream,
+/// so following with it is refused in favor of --jsonl.
+#[test]
+fn logs_rejects_json_with_follow() {
+    cargo_bin_cmd!("pfp")
+        .args(["logs", "some-run", "--json", "-f"])
+        .env("PREFECT_API_URL", "http://127.0.0.1:1")
+        .env_remove("PREFECT_API_AUTH_STRING")
+        .assert()
+        .failure()
+        .code(2)
+        .stderr(predicate::str::contains("use --jsonl"));
+}
+
+#[test]
+fn logs_requires_a_flow_run_or_deployment() {
+    cargo_bin_cmd!("pfp")
+        .args(["logs"])
+        .assert()
+        .failure()
+        .code(2)
+        .stderr(predicate::str::contains("<FLOW_RUN_IDS>"));
+}
+
+#[test]
+fn rejects_unknown_timezone() {
+    cargo_bin_cmd!("pfp")
+        .args(["--tz", "Mars/Olympus", "runs"])
+        .assert()
+        .failure()
+        .code(2)
+        .stderr(predicate::str::contains("unknown timezone 'Mars/Olympus'"));
+}
+
+#[test]
+fn cancel_without_id_or_filters_is_rejected() {
+    cargo_bin_cmd!("pfp")
+        .args(["cancel"])
+        .env("PREFECT_API_URL", "http://127.0.0.1:1")
+        .env_remove("PREFECT_API_AUTH_STRING")
+        .assert()
+        .failure()
+        .code(2)
+        .stderr(predicate::str::contains("give a flow run ID"));
+}
+
+/// Protected pools are refused non-interactively, even with --yes, before
+/// any request is made.
+#[test]
+fn pausing_protected_pool_requires_i_know() {
+    let dir = tempfile::tempdir().unwrap();
+    let settings = dir.path().join("config.toml");
+    std::fs::write(&settings, "[protect]\npools = [\"*-prod\"]\n").unwrap();
+
+    cargo_bin_cmd!("pfp")
+        .args(["pool", "pause", "docker-prod", "--yes"])
+        .env("PFP_CONFIG", &settings)
+        .env("PREFECT_API_URL", "http://127.0.0.1:1")
+        .env_remove("PREFECT_API_AUTH_STRING")
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains("--i-know"));
+}
+
+/// Read-only mode refuses writes with exit code 3 without contacting the
+/// server (the URL below is unreachable).
+#[test]
+fn read_only_mode_refuses_pool_pause() {
+    let dir = tempfile::tempdir().unwrap();
+
+    cargo_bin_cmd!("pfp")
+        .args(["pool", "pause", "docker-secure"])
+        .env("PFP_READ_ONLY", "1")
+        .env("PFP_CONFIG", dir.path().join("missing.toml"))
+        .env("PREFECT_API_URL", "http://127.0.0.1:1")
+        .env_remove("PREFECT_API_AUTH_STRING")
+        .assert()
+        .code(3)
+        .stderr(predicate::str::contains("Read-only mode"));
+}
+
+/// `$PFP_POLICY` cannot replace an existing `~/.pfp/policy.toml` with a
+/// laxer file.
+#[test]
+fn policy_env_does_not_override_home_policy() {
+    let home = tempfile::tempdir().unwrap();
+    let pfp_dir = home.path().join(".pfp");
+    std::fs::create_dir_all(&pfp_dir).unwrap();
+    std::fs::write(pfp_dir.join("policy.toml"), "[pause]\npools = []\n").unwrap();
+    let lax = home.path().join("lax.toml");
+    std::fs::write(&lax, "").unwrap();
+
+    cargo_bin_cmd!("pfp")
+        .args(["pool", "pause", "docker-secure", "--yes"])
+        .env("HOME", home.path())
+        .env("PFP_POLICY", &lax)
+        .env("PFP_CONFIG", home.path().join("missing.toml"))
+        .env("PREFECT_API_URL", "http://127.0.0.1:1")
+        .env_remove("PREFECT_API_AUTH_STRING")
+        .assert()
+        .code(3)
+        .stderr(predicate::str::contains("ignoring $PFP_POLICY"))
+        .stderr(predicate::str::contains("Denied by policy"));
+}
+
+/// A command notifier's output goes to stderr, so `--json` stays parseable.
+#[cfg(unix)]
+#[test]
+fn wait_notify_hook_output_stays_off_stdout() {
+    let mut server = mockito::Server::new();
+    server
+        .mock("POST", "/flow_runs/filter")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"[{"id":"run-a","name":"a","state_type":"COMPLETED","state_name":"Completed"}]"#,
+        )
+        .create();
+    let home = tempfile::tempdir().unwrap();
+    let settings = home.path().join("config.toml");
+    std::fs::write(
+        &settings,
+        "[[notify]]\ntype = \"command\"\ncommand = [\"echo\", \"hook-output\"]\n",
+    )
+    .unwrap();
+
+    let output = cargo_bin_cmd!("pfp")
+        .args(["wait", "run-a", "--json", "--notify"])
+        .env("HOME", home.path())
+        .env("PFP_CONFIG", &settings)
+        .env("PREFECT_API_URL", server.url())
+        .env_remove("PREFECT_API_AUTH_STRING")
+        .env_remove("PFP_POLICY")
+        .output()
+        .unwrap();
+
+    assert!(output.status.success(), "{:?}", output);
+    let results: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(results[0]["state"], "Completed");
+    assert!(String::from_utf8_lossy(&output.stderr).contains("hook-output"));
+}
+
+#[test]
+fn history_reads_rotated_logs_and_filters() {
+    let home = tempfile::tempdir().unwrap();
+    let log_dir = home.path().join(".pfp");
+    std::fs::create_dir_all(&log_dir).unwrap();
+    let record = |command: &str, outcome: &str, query: &str| {
+        format!(
+            r#"{{"version":"0.5.8","ts":"2026-03-10T12:00:00.000Z","command":"{}","args":{{"query":"{}"}},"outcome":"{}","duration_ms":120}}"#,
+            command, query, outcome
+        )
+    };
+    std::fs::write(
+        log_dir.join("pfp.jsonl.1"),
+        record("run", "error", "happy-t") + "\n",
+    )
+    .unwrap();
+    std::fs::write(
+        log_dir.join("pfp.jsonl"),
+        record("run", "ok", "other") + "\n" + &record("ls", "ok", "") + "\n",
+    )
+    .unwrap();
+
+    cargo_bin_cmd!("pfp")
+        .args(["history", "--command", "run", "--errors", "--json"])
+        .env("HOME", home.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(r#""query": "happy-t""#))
+        .stdout(predicate::str::contains("other").not());
+
+    cargo_bin_cmd!("pfp")
+        .args(["history", "--summary", "--json"])
+        .env("HOME", home.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(r#""command": "run""#))
+        .stdout(predicate::str::contains(r#""count": 2"#));
+}
+
+#[test]
+fn invocation_record_carries_result_metadata() {
+    let mut server = mockito::Server::new();
+    server
+        .mock("PATCH", "/work_pools/docker-secure")
+        .with_status(204)
+        .create();
+    server
+        .mock("GET", "/work_pools/docker-secure")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"name":"docker-secure","is_paused":true,"status":"PAUSED"}"#)
+        .create();
+    let home = tempfile::tempdir().unwrap();
+
+    cargo_bin_cmd!("pfp")
+        .args(["pool", "pause", "docker-secure"])
+        .env("HOME", home.path())
+        .env("PREFECT_API_URL", server.url())
+        .env_remove("PREFECT_API_AUTH_STRING")
+        .env_remove("PFP_CONFIG")
+        .env_remove("PFP_POLICY")
+        .assert()
+        .success();
+
+    let log = std::fs::read_to_string(home.path().join(".pfp").join("pfp.jsonl")).unwrap();
+    let record: serde_json::Value = serde_json::from_str(log.lines().last().unwrap()).unwrap();
+    assert_eq!(record["result"]["work_pool"], "docker-secure");
+    assert_eq!(record["result"]["paused"], true);
+    assert_eq!(record["result"]["server"], server.url());
+    assert!(record["result"]["profile"].is_null());
+}
+
+#[test]
+fn json_and_jsonl_conflict() {
+    cargo_bin_cmd!("pfp")
+        .args(["runs", "--json", "--jsonl"])
+        .assert()
+        .failure()
+        .code(2)
+        .stderr(predicate::str::contains("cannot be used with"));
+}
+
+#[test]
+fn pool_ls_json_includes_nonterminal_run_counts() {
+    let mut server = mockito::Server::new();
+    let pools = server
+        .mock("POST", "/work_pools/filter")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"[{"name":"docker-dev","type":"docker","is_paused":false,"status":"READY","concurrency_limit":4},
+                {"name":"docker-prod","type":"docker","is_paused":true,"status":"PAUSED"}]"#,
+        )
+        .expect(1)
+        .create();
+    let mut counts = Vec::new();
+    for (pool, status, body) in [("docker-dev", 200, "3"), ("docker-prod", 500, "")] {
+        counts.push(
+            server
+                .mock("POST", "/flow_runs/count")
+                .match_body(mockito::Matcher::PartialJsonString(format!(
+                    r#"{{"work_pools":{{"name":{{"any_":["{pool}"]}}}}}}"#
+                )))
+                .with_status(status)
+                .with_header("content-type", "application/json")
+                .with_body(body)
+                .expect(1)
+                .create(),
+        );
+    }
+
+    let output = cargo_bin_cmd!("pfp")
+        .args(["pool", "ls", "--json"])
+        .env("PREFECT_API_URL", server.url())
+        .env_remove("PREFECT_API_AUTH_STRING")
+        .assert()
+        .success()
+        .stderr(predicate::str::contains(
+            "could not count flow runs of docker-prod",
+        ))
+        .get_output()
+        .stdout
+        .clone();
+
+    let listed: serde_json::Value = serde_json::from_slice(&output).unwrap();
+    assert_eq!(listed[0]["name"], "docker-dev");
+    assert_eq!(listed[0]["concurrency_limit"], 4);
+    assert_eq!(listed[0]["nonterminal_run_count"], 3);
+    assert_eq!(listed[1]["is_paused"], true);
+    assert!(listed[1]["nonterminal_run_count"].is_null());
+    pools.assert();
+    for count in counts {
+        count.assert();
+    }
+}
+
 #[test]
 fn pool_assert_idle_json_succeeds_with_stable_result() {
     let mut server = mockito::Server::new();